@@ -0,0 +1,46 @@
+//! Benchmarks for validating the characters of a [`Value`].
+//!
+//! Compares [`Value::new`], which scans bytes against a lookup table, with
+//! the `chars()`-based scan it replaced, on values of the maximum length.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use password_hash::Value;
+
+/// Valid value of the maximum length.
+const VALID: &str = "ZEFEW6oONbYtH7ng/a1x+2hJ.cj3P-4kvsDrVQu5qpUzmXlgLILtgeO9Rwi8fTBS";
+
+/// Value of the maximum length whose last character is invalid (and not
+/// ASCII, so it must be decoded to be reported).
+const INVALID: &str = "ZEFEW6oONbYtH7ng/a1x+2hJ.cj3P-4kvsDrVQu5qpUzmXlgLILtgeO9Rwi8fTé";
+
+/// Validation as it was before the byte scan: decode each `char`, and
+/// report the first invalid one along with its byte offset.
+fn validate_chars(input: &str) -> Result<(), (char, usize)> {
+    match input.char_indices().find(|&(_, c)| !is_char_valid(c)) {
+        Some((i, c)) => Err((c, i)),
+        None => Ok(()),
+    }
+}
+
+/// Characters allowed in a [`Value`].
+fn is_char_valid(c: char) -> bool {
+    matches!(c, 'A' ..= 'Z' | 'a'..='z' | '0'..='9' | '/' | '+' | '.' | '-')
+}
+
+fn validate(c: &mut Criterion) {
+    assert_eq!(VALID.len(), Value::MAX_LENGTH);
+    assert_eq!(INVALID.len(), Value::MAX_LENGTH);
+
+    for (name, input) in [("valid", VALID), ("invalid", INVALID)] {
+        assert_eq!(Value::new(input).is_ok(), validate_chars(input).is_ok());
+
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function("bytes", |b| b.iter(|| Value::new(black_box(input))));
+        group.bench_function("chars", |b| b.iter(|| validate_chars(black_box(input))));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, validate);
+criterion_main!(benches);
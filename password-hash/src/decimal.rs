@@ -0,0 +1,447 @@
+//! Decimal (i.e. integer) values as defined by the [PHC string format].
+//!
+//! Implements the following parts of the specification:
+//!
+//! > For an integer value x, its decimal encoding consist in the following:
+//! >
+//! > - If x < 0, then its decimal encoding is the minus sign - followed by the decimal
+//! >   encoding of -x.
+//! > - If x = 0, then its decimal encoding is the single character 0.
+//! > - If x > 0, then its decimal encoding is the smallest sequence of ASCII digits that
+//! >   matches its value (i.e. there is no leading zero).
+//!
+//! [PHC string format]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#decimal-encoding
+
+use crate::errors::InvalidValue;
+use crate::{Error, Result};
+use core::str;
+
+#[cfg(feature = "arbitrary")]
+use crate::{Value, ValueBuf};
+
+/// Type used to represent decimal (i.e. integer) values.
+pub type Decimal = u32;
+
+/// Unsigned integer types which can be parsed from a PHC-encoded decimal
+/// using [`Value::decimal_as`][`crate::Value::decimal_as`].
+///
+/// This trait is sealed and implemented for all of the unsigned primitive
+/// integer types.
+pub trait FromDecimal: str::FromStr + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed: Sized {
+        /// Zero, i.e. the value of an integer with no digits yet.
+        const ZERO: Self;
+
+        /// Append a digit in the given radix, returning `None` on overflow.
+        fn checked_push_digit(self, radix: u32, digit: u32) -> Option<Self>;
+    }
+}
+
+macro_rules! impl_from_decimal {
+    ($($int:ty),+) => {
+        $(
+            impl FromDecimal for $int {}
+
+            impl sealed::Sealed for $int {
+                const ZERO: Self = 0;
+
+                fn checked_push_digit(self, radix: u32, digit: u32) -> Option<Self> {
+                    self.checked_mul(<$int>::try_from(radix).ok()?)?
+                        .checked_add(<$int>::try_from(digit).ok()?)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_decimal!(u8, u16, u32, u64, u128, usize);
+
+/// Canonical PHC-encoded decimal, for generating arbitrary decimal values
+/// with the [`arbitrary`] crate.
+///
+/// The contained value always parses successfully with
+/// [`Value::decimal_signed`], i.e. it may be negative.
+#[cfg(feature = "arbitrary")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbitraryDecimal(ValueBuf);
+
+#[cfg(feature = "arbitrary")]
+impl ArbitraryDecimal {
+    /// Borrow the decimal as a [`Value`].
+    pub fn as_value(&self) -> Value<'_> {
+        self.0.as_value()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl From<ArbitraryDecimal> for ValueBuf {
+    fn from(decimal: ArbitraryDecimal) -> ValueBuf {
+        decimal.0
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ArbitraryDecimal {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let n: i64 = u.arbitrary()?;
+
+        // Large enough for `i64::MIN`
+        let mut buf = [0u8; 20];
+        let mut pos = buf.len();
+        let mut magnitude = n.unsigned_abs();
+
+        loop {
+            pos -= 1;
+            buf[pos] = b'0' + (magnitude % 10) as u8;
+            magnitude /= 10;
+
+            if magnitude == 0 {
+                break;
+            }
+        }
+
+        if n < 0 {
+            pos -= 1;
+            buf[pos] = b'-';
+        }
+
+        let s = str::from_utf8(&buf[pos..]).expect("decimal is ASCII");
+        Ok(Self(ValueBuf::new(s).expect("decimal is a valid value")))
+    }
+}
+
+/// Maximum number of digits in a PHC-encoded [`Decimal`].
+///
+/// Any longer sequence of digits is guaranteed to overflow a [`Decimal`],
+/// and is rejected with [`InvalidValue::TooLong`].
+pub const MAX_DECIMAL_DIGITS: usize = max_digits(10);
+
+/// Compute the number of digits in [`Decimal::MAX`] in the given radix.
+const fn max_digits(radix: u32) -> usize {
+    let mut n = Decimal::MAX;
+    let mut digits = 0;
+
+    while n > 0 {
+        n /= radix;
+        digits += 1;
+    }
+
+    digits
+}
+
+/// Parse a PHC-encoded (unsigned) [`Decimal`] directly from bytes.
+///
+/// This applies exactly the same rules as
+/// [`Value::decimal`][`crate::Value::decimal`], which is implemented in
+/// terms of this function, but doesn't require constructing a
+/// [`Value`][`crate::Value`] first.
+pub fn parse_decimal(bytes: &[u8]) -> Result<Decimal> {
+    parse_radix(bytes, 10)
+}
+
+/// Parse a [`Decimal`] from human-entered input containing grouping
+/// separators, e.g. `65,536` with `sep` as `,`.
+///
+/// This is a convenience for user interfaces and is not part of the PHC
+/// string format: as separators such as `,` aren't valid in a
+/// [`Value`][`crate::Value`], it operates on raw strings. Every occurrence
+/// of `sep` is removed and the remaining digits are parsed with the same
+/// rules as [`parse_decimal`], so e.g. `0,065` is still rejected. The
+/// separator can't appear at the start or end of the input or twice in a
+/// row, which is reported as [`InvalidValue::InvalidFormat`], but the size of
+/// the groups isn't checked.
+///
+/// Any character other than a digit or `sep` (including non-ASCII ones) is
+/// reported as [`InvalidValue::InvalidChar`], even if it follows more than
+/// [`MAX_DECIMAL_DIGITS`] digits.
+pub fn parse_decimal_grouped(input: &str, sep: char) -> Result<Decimal> {
+    let mut digits = [0u8; MAX_DECIMAL_DIGITS];
+    let mut len = 0;
+
+    for group in input.split(sep) {
+        if group.is_empty() && !input.is_empty() {
+            return Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+        }
+
+        for c in group.chars() {
+            if !c.is_ascii_digit() {
+                return Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(c)));
+            }
+
+            // Keep counting past the end of the buffer, so the rest of the
+            // input is still checked for invalid characters
+            if let Some(digit) = digits.get_mut(len) {
+                *digit = c as u8;
+            }
+
+            len += 1;
+        }
+    }
+
+    if len > MAX_DECIMAL_DIGITS {
+        return Err(Error::ParamValueInvalid(InvalidValue::TooLong));
+    }
+
+    parse_decimal(&digits[..len])
+}
+
+/// Write the canonical PHC encoding of a [`Decimal`] to the start of the
+/// provided buffer, returning it as a `str`.
+///
+/// The output is the shortest sequence of digits (i.e. without leading
+/// zeroes, and `0` for zero), which is exactly what [`parse_decimal`]
+/// accepts.
+///
+/// # Panics
+/// If `buf` is too short to hold the output. A buffer of
+/// [`MAX_DECIMAL_DIGITS`] bytes is always sufficient.
+pub fn format_decimal(value: Decimal, buf: &mut [u8]) -> &str {
+    let mut digits = 1;
+    let mut n = value;
+
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+
+    let buf = &mut buf[..digits];
+    let mut n = value;
+
+    for byte in buf.iter_mut().rev() {
+        *byte = b'0' + (n % 10) as u8;
+        n /= 10;
+    }
+
+    str::from_utf8(buf).expect("decimal is ASCII")
+}
+
+/// Parse an unsigned [`Decimal`] from bytes using the given radix, applying
+/// the same rules as [`parse_decimal`] with the radix's digit set.
+///
+/// # Panics
+/// If `radix` is not in the range `2..=36`.
+pub(crate) fn parse_radix(bytes: &[u8], radix: u32) -> Result<Decimal> {
+    parse_digits(bytes, radix)
+}
+
+/// Parse an unsigned integer of any [`FromDecimal`] width from bytes using
+/// the given radix. This is the parser underlying every unsigned decimal
+/// conversion, so they all apply the same rules and report the same errors.
+///
+/// The bytes must be a nonempty sequence of digits with no leading zeroes.
+/// Values which overflow `T` are rejected with [`InvalidValue::TooLong`] as
+/// soon as the overflow occurs, so no more than one digit past the maximum
+/// length of a `T` is ever converted.
+///
+/// # Panics
+/// If `radix` is not in the range `2..=36`.
+pub(crate) fn parse_digits<T: FromDecimal>(bytes: &[u8], radix: u32) -> Result<T> {
+    assert_valid_digits_radix(bytes, radix)?;
+
+    bytes
+        .iter()
+        .try_fold(T::ZERO, |acc, &b| {
+            let digit = char::from(b).to_digit(radix)?;
+            acc.checked_push_digit(radix, digit)
+        })
+        .ok_or(Error::ParamValueInvalid(InvalidValue::TooLong))
+}
+
+/// Ensure the given bytes are a nonempty sequence of ASCII digits with no
+/// leading zeroes, i.e. the unsigned portion of a PHC decimal.
+pub(crate) fn assert_valid_digits(digits: &[u8]) -> Result<()> {
+    assert_valid_digits_radix(digits, 10)
+}
+
+/// Ensure the given bytes are a PHC-encoded decimal, i.e. digits as
+/// validated by [`assert_valid_digits`] optionally preceded by a `-` sign,
+/// in which case the first digit can't be `0`.
+pub(crate) fn assert_valid_signed(bytes: &[u8]) -> Result<()> {
+    let digits = match bytes.split_first() {
+        Some((b'-', digits)) => {
+            // The second character of a negative decimal can't be `0`
+            if digits.first() == Some(&b'0') {
+                return Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+            }
+
+            digits
+        }
+        _ => bytes,
+    };
+
+    assert_valid_digits(digits)
+}
+
+/// Parse a signed decimal without the canonical encoding rules, i.e.
+/// tolerating leading zeroes (including after a `-` sign) and `-0`, so that
+/// every encoding of the same integer parses to the same value.
+///
+/// Returns `None` if the bytes aren't a nonempty sequence of ASCII digits
+/// optionally preceded by `-`, or if they overflow an `i64`.
+pub(crate) fn parse_signed_lenient(bytes: &[u8]) -> Option<i64> {
+    let (negative, digits) = match bytes.split_first() {
+        Some((b'-', digits)) => (true, digits),
+        _ => (false, bytes),
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    digits.iter().try_fold(0i64, |acc, &b| {
+        let digit = i64::from(char::from(b).to_digit(10)?);
+        let acc = acc.checked_mul(10)?;
+
+        if negative {
+            acc.checked_sub(digit)
+        } else {
+            acc.checked_add(digit)
+        }
+    })
+}
+
+/// Ensure the given bytes are a nonempty sequence of digits in the given
+/// radix with no leading zeroes.
+///
+/// # Panics
+/// If `radix` is not in the range `2..=36`.
+fn assert_valid_digits_radix(digits: &[u8], radix: u32) -> Result<()> {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+
+    // Empty strings aren't decimals
+    if digits.is_empty() {
+        return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
+    }
+
+    // Ensure all characters are digits
+    for &b in digits {
+        if !char::from(b).is_digit(radix) {
+            return Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(
+                char::from(b),
+            )));
+        }
+    }
+
+    // Disallow leading zeroes
+    if digits[0] == b'0' && digits.len() > 1 {
+        return Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_decimal, max_digits, parse_decimal, parse_decimal_grouped, parse_signed_lenient,
+        Decimal, Error, InvalidValue, MAX_DECIMAL_DIGITS,
+    };
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn parse_valid() {
+        assert_eq!(parse_decimal(b"0").unwrap(), 0);
+        assert_eq!(parse_decimal(b"65536").unwrap(), 65536);
+        assert_eq!(parse_decimal(b"4294967295").unwrap(), u32::MAX);
+    }
+
+    #[test]
+    fn format_roundtrip() {
+        let mut buf = [0u8; MAX_DECIMAL_DIGITS];
+        assert_eq!(format_decimal(0, &mut buf), "0");
+        assert_eq!(format_decimal(10, &mut buf), "10");
+        assert_eq!(format_decimal(u32::MAX, &mut buf), "4294967295");
+
+        let mut lcg = Lcg::new(1);
+        for _ in 0..10_000 {
+            let n: Decimal = lcg.next_u32();
+
+            for &x in &[n, n >> 8, n >> 16, n >> 24] {
+                let s = format_decimal(x, &mut buf);
+                assert_eq!(parse_decimal(s.as_bytes()), Ok(x));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn format_buffer_too_small() {
+        format_decimal(100, &mut [0u8; 2]);
+    }
+
+    #[test]
+    fn max_decimal_digits() {
+        assert_eq!(MAX_DECIMAL_DIGITS, "4294967295".len());
+        assert_eq!(max_digits(2), 32);
+        assert_eq!(max_digits(16), 8);
+        assert_eq!(max_digits(36), 7);
+    }
+
+    #[test]
+    fn parse_grouped() {
+        assert_eq!(parse_decimal_grouped("65,536", ','), Ok(65536));
+        assert_eq!(parse_decimal_grouped("4_294_967_295", '_'), Ok(u32::MAX));
+        assert_eq!(parse_decimal_grouped("7", ','), Ok(7));
+
+        for &(input, err) in &[
+            ("", InvalidValue::Malformed),
+            (",536", InvalidValue::InvalidFormat),
+            ("65,", InvalidValue::InvalidFormat),
+            ("65,,536", InvalidValue::InvalidFormat),
+            ("0,065", InvalidValue::InvalidFormat),
+            ("65.536", InvalidValue::InvalidChar('.')),
+            ("10,000,000,000", InvalidValue::TooLong),
+            ("\u{e9}", InvalidValue::InvalidChar('\u{e9}')),
+            ("6\u{5f0}", InvalidValue::InvalidChar('\u{5f0}')),
+            ("1000000000x5", InvalidValue::InvalidChar('x')),
+            (
+                "1,000,000,000,000\u{e9}",
+                InvalidValue::InvalidChar('\u{e9}'),
+            ),
+        ] {
+            assert_eq!(
+                parse_decimal_grouped(input, ','),
+                Err(Error::ParamValueInvalid(err)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn signed_lenient() {
+        for &(bytes, n) in &[
+            (&b"0"[..], 0),
+            (b"-0", 0),
+            (b"000", 0),
+            (b"42", 42),
+            (b"0042", 42),
+            (b"-042", -42),
+            (b"9223372036854775807", i64::MAX),
+            (b"-9223372036854775808", i64::MIN),
+        ] {
+            assert_eq!(parse_signed_lenient(bytes), Some(n));
+        }
+
+        for &bytes in &[&b""[..], b"-", b"+1", b"1-", b"0x1", b"9223372036854775808"] {
+            assert_eq!(parse_signed_lenient(bytes), None);
+        }
+    }
+
+    #[test]
+    fn reject_invalid() {
+        for &(bytes, err) in &[
+            (&b""[..], InvalidValue::Malformed),
+            (b"01", InvalidValue::InvalidFormat),
+            (b"4294967296", InvalidValue::TooLong),
+            (b"10000000000", InvalidValue::TooLong),
+            (b"1000000000x", InvalidValue::InvalidChar('x')),
+            (b"-1", InvalidValue::InvalidChar('-')),
+            (b"1,2", InvalidValue::InvalidChar(',')),
+        ] {
+            assert_eq!(parse_decimal(bytes), Err(Error::ParamValueInvalid(err)));
+        }
+    }
+}
@@ -1,11 +1,25 @@
-//! Base64 encoding variants.
+//! Binary-to-text encodings, i.e. the Base64 variants and hexadecimal.
 
 use base64ct::{
-    Base64Bcrypt, Base64Crypt, Base64Unpadded as B64, Encoding as _, Error as B64Error,
+    Base64, Base64Bcrypt, Base64Crypt, Base64Unpadded as B64, Base64UrlUnpadded, Encoding as _,
+    Error as B64Error, InvalidLengthError,
 };
+use core::str;
 
-/// Base64 encoding variants.
+#[cfg(feature = "alloc")]
+use {
+    crate::{Error, Value},
+    alloc::{string::String, vec::Vec},
+    core::ops::Range,
+};
+
+/// Binary-to-text encodings: the Base64 variants used by password hashes,
+/// along with hexadecimal.
+///
+/// New encodings may be added in future releases, so this enum is
+/// `#[non_exhaustive]`.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum Encoding {
     /// "B64" encoding: standard Base64 without padding.
     ///
@@ -31,6 +45,51 @@ pub enum Encoding {
     /// 0x2e-0x39, 0x41-0x5a, 0x61-0x7a
     /// ```
     Crypt,
+
+    /// Hexadecimal (i.e. Base16) encoding.
+    ///
+    /// ```text
+    /// [0-9]      [a-f]      [A-F]
+    /// 0x30-0x39, 0x61-0x66, 0x41-0x46
+    /// ```
+    ///
+    /// This is not part of the PHC string format specification, but is used
+    /// by some KDF parameters in the wild. Encoding produces lowercase output,
+    /// and decoding accepts either case.
+    Hex,
+
+    /// Standard Base64 encoding (RFC 4648, section 4) with optional padding.
+    ///
+    /// ```text
+    /// [A-Z]      [a-z]      [0-9]      +     /     =
+    /// 0x41-0x5a, 0x61-0x7a, 0x30-0x39, 0x2b, 0x2f, 0x3d
+    /// ```
+    ///
+    /// This is a compatibility shim for non-conformant producers and is
+    /// **not** part of the PHC string format. Encoding produces padded
+    /// output, which is not a valid [`Value`][`crate::Value`]. Decoding
+    /// tolerates either padded or unpadded input; as `=` can't occur in a
+    /// [`Value`][`crate::Value`], decoding a value only accepts the unpadded
+    /// form, which is identical to [`Encoding::B64`].
+    Base64Standard,
+
+    /// URL-safe Base64 encoding (RFC 4648, section 5) without padding.
+    ///
+    /// ```text
+    /// [A-Z]      [a-z]      [0-9]      -     _
+    /// 0x41-0x5a, 0x61-0x7a, 0x30-0x39, 0x2d, 0x5f
+    /// ```
+    ///
+    /// This is an interoperability shim for payloads produced by web systems
+    /// (e.g. JWTs) and is **not** part of the PHC string format. While `-`
+    /// is a valid [`Value`][`crate::Value`] character, `_` is not. Payloads
+    /// which happen not to contain `_` can be parsed as a value and decoded
+    /// with [`Value::decode_with`][`crate::Value::decode_with`], but those
+    /// which do can't be represented as a value at all (even
+    /// [`Value::from_percent_encoded`][`crate::Value::from_percent_encoded`]
+    /// rejects a decoded `_`), and must be decoded from the raw string with
+    /// [`Encoding::decode`] instead.
+    B64UrlSafe,
 }
 
 impl Default for Encoding {
@@ -41,11 +100,133 @@ impl Default for Encoding {
 
 impl Encoding {
     /// Decode a Base64 string into the provided destination buffer.
+    ///
+    /// Input whose final character has non-zero unused low bits, e.g. `AB`
+    /// rather than `AA`, is rejected with [`B64Error::InvalidEncoding`], so
+    /// that every byte string has exactly one encoding.
     pub fn decode(self, src: impl AsRef<[u8]>, dst: &mut [u8]) -> Result<&[u8], B64Error> {
+        let src = match self {
+            Self::Base64Standard => strip_padding(src.as_ref())?,
+            _ => src.as_ref(),
+        };
+
+        let decoded = match self {
+            Self::B64 | Self::Base64Standard => B64::decode(src, dst)?,
+            Self::Bcrypt => Base64Bcrypt::decode(src, dst)?,
+            Self::Crypt => Base64Crypt::decode(src, dst)?,
+            Self::Hex => return hex_decode(src, dst),
+            Self::B64UrlSafe => Base64UrlUnpadded::decode(src, dst)?,
+        };
+
+        self.check_tail(src, decoded)?;
+        Ok(decoded)
+    }
+
+    /// Is the given string validly encoded under this encoding?
+    ///
+    /// This checks the characters and the length of the input without
+    /// decoding it, aside from the final partial group (if any), whose
+    /// unused bits must be zero. If it returns `true`, [`Encoding::decode`]
+    /// won't fail, provided the destination buffer is large enough.
+    pub fn is_valid(self, input: &str) -> bool {
+        let input = match self {
+            Self::Base64Standard => match strip_padding(input.as_bytes()) {
+                Ok(unpadded) => unpadded,
+                Err(_) => return false,
+            },
+            _ => input.as_bytes(),
+        };
+
+        self.decoded_len(input.len()).is_ok()
+            && input.iter().all(|&b| self.is_alphabet_byte(b))
+            && self.validate_tail(input).is_ok()
+    }
+
+    /// Guess the encoding of the given string, for best-effort importing of
+    /// values from non-conformant sources.
+    ///
+    /// The candidates are tried in the following order, and the first under
+    /// which the input is [valid][`Encoding::is_valid`] is returned:
+    ///
+    /// 1. [`Encoding::Hex`]: even-length strings of hex digits
+    /// 2. [`Encoding::B64`]
+    /// 3. [`Encoding::Base64Standard`]: i.e. padded Base64
+    ///
+    /// The empty string is reported as [`Encoding::B64`]. The result always
+    /// decodes successfully, but note that many strings are valid under more
+    /// than one encoding, and in particular that an even number of decimal
+    /// digits is detected as hex. Use [`Value::classify`][`crate::Value::classify`]
+    /// first if decimals must be told apart. [`Encoding::Bcrypt`] and
+    /// [`Encoding::Crypt`] are never detected, as their alphabets can't be
+    /// distinguished from B64.
+    pub fn detect(input: &str) -> Option<Self> {
+        if input.is_empty() {
+            return Some(Self::B64);
+        }
+
+        [Self::Hex, Self::B64, Self::Base64Standard]
+            .into_iter()
+            .find(|encoding| encoding.is_valid(input))
+    }
+
+    /// Decode a string into the provided destination buffer in constant
+    /// time, i.e. without data-dependent branches or table lookups, making
+    /// it suitable for secret material such as the `data` parameter of the
+    /// [Argon2 Encoding][1].
+    ///
+    /// This produces identical output to [`Encoding::decode`] for valid
+    /// input. The Base64 variants are all decoded by the constant-time
+    /// implementation in `base64ct` (as is the case for
+    /// [`Encoding::decode`]), whereas hex is decoded with a slower
+    /// branch-free implementation. The lengths of the input and output, the
+    /// position of any [`Encoding::Base64Standard`] padding, and whether the
+    /// input is valid are still revealed, as they are structural rather than
+    /// secret.
+    ///
+    /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
+    pub fn decode_ct(self, src: impl AsRef<[u8]>, dst: &mut [u8]) -> Result<&[u8], B64Error> {
         match self {
-            Self::B64 => B64::decode(src, dst),
-            Self::Bcrypt => Base64Bcrypt::decode(src, dst),
-            Self::Crypt => Base64Crypt::decode(src, dst),
+            Self::Hex => hex_decode_ct(src.as_ref(), dst),
+            _ => self.decode(src, dst),
+        }
+    }
+
+    /// Decode the encoded string contained in `buf` in place, overwriting
+    /// the front of the buffer with the decoded bytes and returning them.
+    ///
+    /// The decoded output is always shorter than the encoded input, so no
+    /// second buffer is required. The input is validated against this
+    /// encoding's alphabet and length rules before `buf` is modified, so
+    /// `buf` is left unchanged on error.
+    pub fn decode_in_place(self, buf: &mut [u8]) -> Result<&[u8], B64Error> {
+        let unpadded_len = match self {
+            Self::Base64Standard => strip_padding(buf)?.len(),
+            _ => buf.len(),
+        };
+
+        let buf = &mut buf[..unpadded_len];
+        let len = self.decoded_len(buf.len())?;
+
+        if !buf.iter().all(|&b| self.is_alphabet_byte(b)) {
+            return Err(B64Error::InvalidEncoding);
+        }
+
+        self.validate_tail(buf)?;
+
+        match self {
+            Self::B64 | Self::Base64Standard => Ok(B64::decode_in_place(buf)?),
+            Self::Bcrypt => Ok(Base64Bcrypt::decode_in_place(buf)?),
+            Self::Crypt => Ok(Base64Crypt::decode_in_place(buf)?),
+            Self::B64UrlSafe => Ok(Base64UrlUnpadded::decode_in_place(buf)?),
+            Self::Hex => {
+                // Output byte `i` is read from input bytes `2i` and `2i + 1`,
+                // which are never behind the write position.
+                for i in 0..len {
+                    buf[i] = (hex_nibble(buf[2 * i])? << 4) | hex_nibble(buf[2 * i + 1])?;
+                }
+
+                Ok(&buf[..len])
+            }
         }
     }
 
@@ -58,16 +239,819 @@ impl Encoding {
             Self::B64 => B64::encode(src, dst),
             Self::Bcrypt => Base64Bcrypt::encode(src, dst),
             Self::Crypt => Base64Crypt::encode(src, dst),
+            Self::Hex => hex_encode(src, dst),
+            Self::Base64Standard => Base64::encode(src, dst),
+            Self::B64UrlSafe => Base64UrlUnpadded::encode(src, dst),
         }
         .map_err(Into::into)
     }
 
+    /// Encode the input byte slice as Base64, returning a newly allocated
+    /// ASCII-encoded Base64 string value without padding.
+    #[cfg(feature = "alloc")]
+    pub fn encode_vec(self, src: &[u8]) -> String {
+        match self {
+            Self::B64 => B64::encode_string(src),
+            Self::Bcrypt => Base64Bcrypt::encode_string(src),
+            Self::Crypt => Base64Crypt::encode_string(src),
+            Self::Hex => {
+                let mut buf = alloc::vec![0u8; src.len() * 2];
+                hex_encode(src, &mut buf).expect("hex buffer too small");
+                String::from_utf8(buf).expect("hex output is ASCII")
+            }
+            Self::Base64Standard => Base64::encode_string(src),
+            Self::B64UrlSafe => Base64UrlUnpadded::encode_string(src),
+        }
+    }
+
+    /// Decode each of the given values contiguously into the provided
+    /// destination buffer, returning the range of `dst` occupied by each of
+    /// the decoded values.
+    ///
+    /// On failure, returns the index of the value which couldn't be decoded
+    /// along with the error, e.g. if it was invalid or `dst` was too small.
+    #[cfg(feature = "alloc")]
+    pub fn decode_all(
+        self,
+        values: &[Value<'_>],
+        dst: &mut [u8],
+    ) -> Result<Vec<Range<usize>>, (usize, Error)> {
+        let mut ranges = Vec::with_capacity(values.len());
+        let mut offset = 0;
+
+        for (i, value) in values.iter().enumerate() {
+            let len = value
+                .decode_with(self, &mut dst[offset..])
+                .map_err(|err| (i, err))?
+                .len();
+
+            ranges.push(offset..(offset + len));
+            offset += len;
+        }
+
+        Ok(ranges)
+    }
+
     /// Get the length of Base64 produced by encoding the given bytes.
     pub fn encoded_len(self, bytes: &[u8]) -> usize {
+        self.encoded_len_for(bytes.len())
+    }
+
+    /// Get the length of the string produced by encoding `decoded_len` bytes
+    /// with this encoding.
+    ///
+    /// This allows sizing a buffer for [`Encoding::encode`] in advance, e.g.
+    /// as the length of a stack-allocated array.
+    pub const fn encoded_len_for(self, decoded_len: usize) -> usize {
+        match self {
+            // All of the Base64 variants are padding-free
+            Self::B64 | Self::Bcrypt | Self::Crypt | Self::B64UrlSafe => {
+                (decoded_len / 3) * 4 + ((decoded_len % 3) * 4 + 2) / 3
+            }
+            Self::Hex => decoded_len * 2,
+            Self::Base64Standard => {
+                (decoded_len / 3 + if decoded_len % 3 == 0 { 0 } else { 1 }) * 4
+            }
+        }
+    }
+
+    /// Get the length of the output produced by decoding a string of the
+    /// given length with this encoding.
+    ///
+    /// Returns [`B64Error::InvalidLength`] if the length is not possible for
+    /// this encoding, e.g. `encoded_len % 4 == 1` for the Base64 variants.
+    ///
+    /// For [`Encoding::Base64Standard`] this assumes unpadded input, and is
+    /// therefore an upper bound for padded input.
+    pub const fn decoded_len(self, encoded_len: usize) -> Result<usize, B64Error> {
         match self {
-            Self::B64 => B64::encoded_len(bytes),
-            Self::Bcrypt => Base64Bcrypt::encoded_len(bytes),
-            Self::Crypt => Base64Crypt::encoded_len(bytes),
+            Self::B64 | Self::Bcrypt | Self::Crypt | Self::Base64Standard | Self::B64UrlSafe => {
+                let remainder = match encoded_len % 4 {
+                    0 => 0,
+                    2 => 1,
+                    3 => 2,
+                    _ => return Err(B64Error::InvalidLength),
+                };
+
+                Ok((encoded_len / 4) * 3 + remainder)
+            }
+            Self::Hex if encoded_len % 2 == 0 => Ok(encoded_len / 2),
+            Self::Hex => Err(B64Error::InvalidLength),
+        }
+    }
+
+    /// Check that the final partial group of the unpadded Base64 input `src`
+    /// (if any) is exactly what encoding the corresponding bytes of
+    /// `decoded` produces, i.e. that its unused low bits are zero.
+    ///
+    /// Older `base64ct` releases silently discard these bits, so they're
+    /// checked here to make decoding agree across versions. Re-encoding keeps
+    /// the check constant time, as required by [`Encoding::decode_ct`].
+    fn check_tail(self, src: &[u8], decoded: &[u8]) -> Result<(), B64Error> {
+        let tail = &src[src.len() - src.len() % 4..];
+
+        if tail.is_empty() {
+            return Ok(());
+        }
+
+        // Re-encode without padding, which the tail never includes
+        let encoding = match self {
+            Self::Base64Standard => Self::B64,
+            _ => self,
+        };
+
+        let mut buf = [0u8; 3];
+        let bytes = &decoded[decoded.len() - (tail.len() - 1)..];
+        let encoded = encoding.encode(bytes, &mut buf)?;
+        let diff = encoded
+            .bytes()
+            .zip(tail)
+            .fold(0, |acc, (a, &b)| acc | (a ^ b));
+
+        if diff == 0 {
+            Ok(())
+        } else {
+            Err(B64Error::InvalidEncoding)
+        }
+    }
+
+    /// Decode just the final partial group of unpadded `src` (if any) into a
+    /// scratch buffer, to validate it without a full-size output buffer.
+    fn validate_tail(self, src: &[u8]) -> Result<(), B64Error> {
+        let tail = &src[src.len() - src.len() % 4..];
+        self.decode(tail, &mut [0u8; 2]).map(|_| ())
+    }
+
+    /// Is the given byte part of this encoding's alphabet?
+    pub(crate) fn is_alphabet_byte(self, byte: u8) -> bool {
+        match self {
+            Self::B64 | Self::Base64Standard => {
+                byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'/'
+            }
+            // Same alphabet in a different order
+            Self::Bcrypt | Self::Crypt => {
+                byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'/'
+            }
+            Self::Hex => byte.is_ascii_hexdigit(),
+            Self::B64UrlSafe => byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_',
+        }
+    }
+}
+
+/// Encoding which can be used to decode a [`Value`][`crate::Value`] with
+/// [`Value::decode_with`][`crate::Value::decode_with`].
+///
+/// This is implemented by [`Encoding`], which covers the encodings used by
+/// PHC and MCF hashes, and can be implemented for other alphabets e.g. for
+/// proprietary algorithms' parameters. The methods mirror those of
+/// [`Encoding`], and report errors using [`B64Error`] regardless of the
+/// alphabet.
+///
+/// ```
+/// use password_hash::{errors::B64Error, Value, ValueEncoding};
+///
+/// /// Encoding in which each character represents itself.
+/// struct Raw;
+///
+/// impl ValueEncoding for Raw {
+///     fn decode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error> {
+///         let dst = dst.get_mut(..src.len()).ok_or(B64Error::InvalidLength)?;
+///         dst.copy_from_slice(src);
+///         Ok(dst)
+///     }
+///
+///     fn encode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a str, B64Error> {
+///         let dst = dst.get_mut(..src.len()).ok_or(B64Error::InvalidLength)?;
+///         dst.copy_from_slice(src);
+///         core::str::from_utf8(dst).map_err(|_| B64Error::InvalidEncoding)
+///     }
+///
+///     fn encoded_len_for(&self, decoded_len: usize) -> usize {
+///         decoded_len
+///     }
+///
+///     fn decoded_len(&self, encoded_len: usize) -> Result<usize, B64Error> {
+///         Ok(encoded_len)
+///     }
+/// }
+///
+/// let value = Value::new("a+b").unwrap();
+/// assert_eq!(value.decode_with(Raw, &mut [0u8; 8]).unwrap(), b"a+b");
+/// ```
+pub trait ValueEncoding {
+    /// Decode `src` into the provided destination buffer, returning the
+    /// decoded bytes.
+    fn decode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error>;
+
+    /// Encode `src` into the provided destination buffer, returning the
+    /// encoded string.
+    fn encode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a str, B64Error>;
+
+    /// Get the length of the string produced by encoding `decoded_len`
+    /// bytes.
+    fn encoded_len_for(&self, decoded_len: usize) -> usize;
+
+    /// Get the length of the output produced by decoding a string of the
+    /// given length, or [`B64Error::InvalidLength`] if that length isn't
+    /// possible for this encoding.
+    fn decoded_len(&self, encoded_len: usize) -> Result<usize, B64Error>;
+}
+
+impl ValueEncoding for Encoding {
+    fn decode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error> {
+        Encoding::decode(*self, src, dst)
+    }
+
+    fn encode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a str, B64Error> {
+        Encoding::encode(*self, src, dst)
+    }
+
+    fn encoded_len_for(&self, decoded_len: usize) -> usize {
+        Encoding::encoded_len_for(*self, decoded_len)
+    }
+
+    fn decoded_len(&self, encoded_len: usize) -> Result<usize, B64Error> {
+        Encoding::decoded_len(*self, encoded_len)
+    }
+}
+
+impl<E: ValueEncoding + ?Sized> ValueEncoding for &E {
+    fn decode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error> {
+        (**self).decode(src, dst)
+    }
+
+    fn encode<'a>(&self, src: &[u8], dst: &'a mut [u8]) -> Result<&'a str, B64Error> {
+        (**self).encode(src, dst)
+    }
+
+    fn encoded_len_for(&self, decoded_len: usize) -> usize {
+        (**self).encoded_len_for(decoded_len)
+    }
+
+    fn decoded_len(&self, encoded_len: usize) -> Result<usize, B64Error> {
+        (**self).decoded_len(encoded_len)
+    }
+}
+
+/// Incremental decoder for [`Encoding::B64`], for input which arrives in
+/// chunks rather than as a single string.
+///
+/// Complete 4-character groups are decoded as soon as they're available, and
+/// any trailing partial group is buffered until the next call to
+/// [`B64Decoder::update`] or [`B64Decoder::finalize`], so chunks may be split
+/// at arbitrary positions. The output is identical to decoding the
+/// concatenated input with [`Encoding::decode`].
+///
+/// If an error is returned the decoder's state is unspecified, and it should
+/// be discarded.
+#[derive(Clone, Debug, Default)]
+pub struct B64Decoder {
+    /// Characters of a partial group left over from previous chunks.
+    pending: [u8; 4],
+
+    /// Number of characters in `pending` (always less than 4 between calls).
+    pending_len: usize,
+}
+
+impl B64Decoder {
+    /// Create a new decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the next chunk of input, writing the decoded bytes to `out`
+    /// and returning how many were written.
+    ///
+    /// `out` must have room for 3 bytes for every complete group formed by
+    /// the buffered characters and `chunk`.
+    pub fn update(&mut self, chunk: &str, out: &mut [u8]) -> Result<usize, B64Error> {
+        let mut input = chunk.as_bytes();
+        let mut written = 0;
+
+        if self.pending_len > 0 {
+            let n = input.len().min(4 - self.pending_len);
+            self.pending[self.pending_len..][..n].copy_from_slice(&input[..n]);
+            self.pending_len += n;
+            input = &input[n..];
+
+            if self.pending_len < 4 {
+                return Ok(0);
+            }
+
+            written += decode_group(&self.pending, out)?;
+            self.pending_len = 0;
+        }
+
+        let mut groups = input.chunks_exact(4);
+
+        for group in &mut groups {
+            let out = out.get_mut(written..).ok_or(B64Error::InvalidLength)?;
+            written += decode_group(group, out)?;
+        }
+
+        let rest = groups.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+        Ok(written)
+    }
+
+    /// Decode any remaining buffered input, writing the decoded bytes to
+    /// `out` and returning how many were written.
+    ///
+    /// Returns an error if the buffered characters don't form a valid final
+    /// group, e.g. because the total input wasn't a valid B64 length.
+    pub fn finalize(self, out: &mut [u8]) -> Result<usize, B64Error> {
+        Ok(Encoding::B64
+            .decode(&self.pending[..self.pending_len], out)?
+            .len())
+    }
+}
+
+/// Decode a complete 4-character B64 group into the start of `out`.
+fn decode_group(group: &[u8], out: &mut [u8]) -> Result<usize, B64Error> {
+    let out = out.get_mut(..3).ok_or(B64Error::InvalidLength)?;
+    Ok(B64::decode(group, out)?.len())
+}
+
+/// Strip the trailing `=` padding (if any) from standard Base64 input.
+///
+/// Padded input must be a multiple of 4 characters long.
+fn strip_padding(src: &[u8]) -> Result<&[u8], B64Error> {
+    let unpadded = match src {
+        [rest @ .., b'=', b'='] | [rest @ .., b'='] => rest,
+        _ => return Ok(src),
+    };
+
+    if src.len() % 4 != 0 {
+        return Err(B64Error::InvalidLength);
+    }
+
+    Ok(unpadded)
+}
+
+/// Lowercase hexadecimal alphabet.
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Decode a hexadecimal string into the provided destination buffer.
+fn hex_decode<'a>(src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error> {
+    let len = Encoding::Hex.decoded_len(src.len())?;
+    let dst = dst.get_mut(..len).ok_or(B64Error::InvalidLength)?;
+
+    for (pair, byte) in src.chunks_exact(2).zip(dst.iter_mut()) {
+        *byte = (hex_nibble(pair[0])? << 4) | hex_nibble(pair[1])?;
+    }
+
+    Ok(dst)
+}
+
+/// Encode the input as lowercase hexadecimal into the provided destination.
+fn hex_encode<'a>(src: &[u8], dst: &'a mut [u8]) -> Result<&'a str, InvalidLengthError> {
+    let dst = dst.get_mut(..(src.len() * 2)).ok_or(InvalidLengthError)?;
+
+    for (byte, pair) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        pair[0] = HEX_CHARS[usize::from(byte >> 4)];
+        pair[1] = HEX_CHARS[usize::from(byte & 0xf)];
+    }
+
+    Ok(str::from_utf8(dst).expect("hex output is ASCII"))
+}
+
+/// Decode a hexadecimal string into the provided destination buffer in
+/// constant time.
+fn hex_decode_ct<'a>(src: &[u8], dst: &'a mut [u8]) -> Result<&'a [u8], B64Error> {
+    let len = Encoding::Hex.decoded_len(src.len())?;
+    let dst = dst.get_mut(..len).ok_or(B64Error::InvalidLength)?;
+    let mut err = 0i16;
+
+    for (pair, byte) in src.chunks_exact(2).zip(dst.iter_mut()) {
+        let hi = hex_nibble_ct(pair[0]);
+        let lo = hex_nibble_ct(pair[1]);
+        err |= hi | lo;
+        *byte = ((hi << 4) | lo) as u8;
+    }
+
+    if err < 0 {
+        return Err(B64Error::InvalidEncoding);
+    }
+
+    Ok(dst)
+}
+
+/// Decode a single hexadecimal digit in constant time, returning -1 if it
+/// is invalid.
+///
+/// Each range check computes a mask which is all ones if `c` is within the
+/// range, using the sign bit of `(lo - 1 - c) & (c - (hi + 1))`.
+fn hex_nibble_ct(c: u8) -> i16 {
+    let c = i16::from(c);
+    let mut ret = -1;
+
+    // 0-9: 0x30-0x39
+    ret += (((0x2f - c) & (c - 0x3a)) >> 8) & (c - 0x2f);
+
+    // a-f: 0x61-0x66
+    ret += (((0x60 - c) & (c - 0x67)) >> 8) & (c - 0x56);
+
+    // A-F: 0x41-0x46
+    ret += (((0x40 - c) & (c - 0x47)) >> 8) & (c - 0x36);
+
+    ret
+}
+
+/// Decode a single hexadecimal digit.
+fn hex_nibble(c: u8) -> Result<u8, B64Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(B64Error::InvalidEncoding),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_nibble, hex_nibble_ct, B64Decoder, B64Error, Encoding};
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn hex_roundtrip() {
+        let mut buf = [0u8; 16];
+        let encoded = Encoding::Hex
+            .encode(&[0x00, 0x1f, 0xa0, 0xff], &mut buf)
+            .unwrap();
+        assert_eq!(encoded, "001fa0ff");
+
+        let mut out = [0u8; 4];
+        let decoded = Encoding::Hex.decode("001FA0ff", &mut out).unwrap();
+        assert_eq!(decoded, [0x00, 0x1f, 0xa0, 0xff]);
+    }
+
+    #[test]
+    fn decode_in_place() {
+        let mut buf = *b"AQIDBA";
+        assert_eq!(
+            Encoding::B64.decode_in_place(&mut buf).unwrap(),
+            [1, 2, 3, 4]
+        );
+
+        let mut buf = *b"001fA0ff";
+        assert_eq!(
+            Encoding::Hex.decode_in_place(&mut buf).unwrap(),
+            [0x00, 0x1f, 0xa0, 0xff]
+        );
+
+        // Invalid input is rejected before the buffer is modified
+        let mut buf = *b"AQID.A";
+        assert_eq!(
+            Encoding::B64.decode_in_place(&mut buf),
+            Err(B64Error::InvalidEncoding)
+        );
+        assert_eq!(&buf, b"AQID.A");
+
+        let mut buf = *b"AQIDB";
+        assert_eq!(
+            Encoding::B64.decode_in_place(&mut buf),
+            Err(B64Error::InvalidLength)
+        );
+        assert_eq!(&buf, b"AQIDB");
+    }
+
+    #[test]
+    fn length_calculators() {
+        const BUF_LEN: usize = Encoding::B64.encoded_len_for(32);
+        assert_eq!(BUF_LEN, 43);
+        let mut buf = [0u8; BUF_LEN];
+
+        for &encoding in &[
+            Encoding::B64,
+            Encoding::Bcrypt,
+            Encoding::Crypt,
+            Encoding::Hex,
+        ] {
+            for len in 0..=21 {
+                let bytes = [0xa5u8; 21];
+                let encoded = encoding.encode(&bytes[..len], &mut buf).unwrap();
+                assert_eq!(encoding.encoded_len_for(len), encoded.len());
+                assert_eq!(encoding.decoded_len(encoded.len()), Ok(len));
+            }
+        }
+
+        assert_eq!(Encoding::B64.decoded_len(5), Err(B64Error::InvalidLength));
+        assert_eq!(Encoding::Hex.decoded_len(3), Err(B64Error::InvalidLength));
+    }
+
+    #[test]
+    fn base64_standard() {
+        let mut buf = [0u8; 8];
+        let encoded = Encoding::Base64Standard.encode(&[1], &mut buf).unwrap();
+        assert_eq!(encoded, "AQ==");
+        assert_eq!(Encoding::Base64Standard.encoded_len_for(1), 4);
+        assert_eq!(Encoding::Base64Standard.encoded_len_for(3), 4);
+
+        for &(src, decoded) in &[
+            ("AQ==", &[1][..]),
+            ("AQ", &[1]),
+            ("AQI=", &[1, 2]),
+            ("AQID", &[1, 2, 3]),
+        ] {
+            let mut out = [0u8; 8];
+            assert_eq!(
+                Encoding::Base64Standard.decode(src, &mut out).unwrap(),
+                decoded
+            );
+
+            let mut in_place = [0u8; 4];
+            in_place[..src.len()].copy_from_slice(src.as_bytes());
+            assert_eq!(
+                Encoding::Base64Standard
+                    .decode_in_place(&mut in_place[..src.len()])
+                    .unwrap(),
+                decoded
+            );
+        }
+
+        for &src in &["AQ=", "AQID=", "A==="] {
+            let mut out = [0u8; 8];
+            assert!(Encoding::Base64Standard.decode(src, &mut out).is_err());
+        }
+
+        // Padding is only tolerated by `Base64Standard`
+        assert!(Encoding::B64.decode("AQ==", &mut buf).is_err());
+    }
+
+    #[test]
+    fn hex_nibble_ct_matches() {
+        for c in 0..=u8::MAX {
+            let expected = hex_nibble(c).map(i16::from).unwrap_or(-1);
+            assert_eq!(hex_nibble_ct(c), expected, "{:#04x}", c);
+        }
+    }
+
+    #[test]
+    fn b64_url_safe() {
+        let bytes = [0xfb, 0xff, 0xbf];
+        let mut buf = [0u8; 8];
+        assert_eq!(Encoding::B64.encode(&bytes, &mut buf).unwrap(), "+/+/");
+        assert_eq!(
+            Encoding::B64UrlSafe.encode(&bytes, &mut buf).unwrap(),
+            "-_-_"
+        );
+        assert_eq!(Encoding::B64UrlSafe.encoded_len_for(4), 6);
+
+        let mut out = [0u8; 8];
+        assert_eq!(
+            Encoding::B64UrlSafe.decode("-_-_", &mut out).unwrap(),
+            bytes
+        );
+        assert!(Encoding::B64UrlSafe.decode("+/+/", &mut out).is_err());
+
+        let mut buf = *b"AQID-w";
+        assert_eq!(
+            Encoding::B64UrlSafe.decode_in_place(&mut buf).unwrap(),
+            [1, 2, 3, 0xfb]
+        );
+    }
+
+    #[test]
+    fn detect() {
+        for &(input, expected) in &[
+            ("", Some(Encoding::B64)),
+            ("001fA0ff", Some(Encoding::Hex)),
+            ("1234", Some(Encoding::Hex)),
+            ("120", Some(Encoding::B64)),
+            ("123", None),
+            ("AQID", Some(Encoding::B64)),
+            ("AQIDBA", Some(Encoding::B64)),
+            ("AQIDBA==", Some(Encoding::Base64Standard)),
+            ("A", None),
+            ("a.b", None),
+            ("a;b=", None),
+        ] {
+            assert_eq!(Encoding::detect(input), expected, "{}", input);
+
+            if let Some(encoding) = expected {
+                let mut buf = [0u8; 8];
+                assert!(encoding.decode(input, &mut buf).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn is_valid_matches_decode() {
+        const CHARS: &[u8] = b"AZaz09+/.-_=g";
+
+        let mut lcg = Lcg::default();
+
+        for &encoding in &[
+            Encoding::B64,
+            Encoding::Bcrypt,
+            Encoding::Crypt,
+            Encoding::Hex,
+            Encoding::Base64Standard,
+            Encoding::B64UrlSafe,
+        ] {
+            for _ in 0..1000 {
+                let len = (lcg.next_u32() >> 28) as usize;
+                let mut input = [0u8; 16];
+
+                for byte in &mut input[..len] {
+                    *byte = CHARS[usize::from(lcg.next_byte()) % CHARS.len()];
+                }
+
+                let input = core::str::from_utf8(&input[..len]).unwrap();
+                let mut buf = [0u8; 16];
+                assert_eq!(
+                    encoding.is_valid(input),
+                    encoding.decode(input, &mut buf).is_ok(),
+                    "{:?} {:?}",
+                    encoding,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_valid_matches_decode_short() {
+        // Every string of up to 3 characters over the union of the alphabets,
+        // padding and a character which is never valid
+        const CHARS: &[u8] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+-./=;_";
+
+        for &encoding in &[
+            Encoding::B64,
+            Encoding::Bcrypt,
+            Encoding::Crypt,
+            Encoding::Hex,
+            Encoding::Base64Standard,
+            Encoding::B64UrlSafe,
+        ] {
+            let mut buf = [0u8; 3];
+
+            for len in 0..4 {
+                for n in 0..CHARS.len().pow(len) {
+                    let mut input = [0u8; 3];
+                    let mut rest = n;
+
+                    for byte in &mut input[..len as usize] {
+                        *byte = CHARS[rest % CHARS.len()];
+                        rest /= CHARS.len();
+                    }
+
+                    let input = core::str::from_utf8(&input[..len as usize]).unwrap();
+                    assert_eq!(
+                        encoding.is_valid(input),
+                        encoding.decode(input, &mut buf).is_ok(),
+                        "{:?} {:?}",
+                        encoding,
+                        input
+                    );
+                }
+            }
         }
     }
+
+    #[test]
+    fn reject_nonzero_trailing_bits() {
+        let mut buf = [0u8; 2];
+
+        for &encoding in &[
+            Encoding::B64,
+            Encoding::Bcrypt,
+            Encoding::Crypt,
+            Encoding::Base64Standard,
+            Encoding::B64UrlSafe,
+        ] {
+            for input in ["AB", "AR", "AAB"] {
+                assert!(!encoding.is_valid(input), "{:?} {}", encoding, input);
+                assert_eq!(
+                    encoding.decode(input, &mut buf),
+                    Err(B64Error::InvalidEncoding)
+                );
+                assert_eq!(
+                    encoding.decode_ct(input, &mut buf),
+                    Err(B64Error::InvalidEncoding)
+                );
+
+                let mut in_place = [0u8; 3];
+                in_place[..input.len()].copy_from_slice(input.as_bytes());
+                let in_place = &mut in_place[..input.len()];
+                assert_eq!(
+                    encoding.decode_in_place(in_place),
+                    Err(B64Error::InvalidEncoding)
+                );
+                assert_eq!(in_place, input.as_bytes());
+            }
+        }
+
+        for input in ["AA", "AAE"] {
+            assert!(Encoding::B64.is_valid(input));
+            assert!(Encoding::B64.decode(input, &mut buf).is_ok());
+        }
+    }
+
+    #[test]
+    fn decode_ct() {
+        for &(encoding, src) in &[
+            (Encoding::B64, "AQIDBA"),
+            (Encoding::Bcrypt, "./Az09.."),
+            (Encoding::Crypt, "./Az09.."),
+            (Encoding::Hex, "0123456789abcdefABCDEF"),
+            (Encoding::Base64Standard, "AQIDBA=="),
+            (Encoding::B64UrlSafe, "-_-_"),
+        ] {
+            let mut expected = [0u8; 16];
+            let mut actual = [0u8; 16];
+            assert_eq!(
+                encoding.decode_ct(src, &mut actual).unwrap(),
+                encoding.decode(src, &mut expected).unwrap()
+            );
+        }
+
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            Encoding::Hex.decode_ct("0g", &mut buf),
+            Err(B64Error::InvalidEncoding)
+        );
+        assert_eq!(
+            Encoding::Hex.decode_ct("abc", &mut buf),
+            Err(B64Error::InvalidLength)
+        );
+        assert_eq!(
+            Encoding::Hex.decode_ct("0011", &mut [0u8; 1]),
+            Err(B64Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn hex_reject_invalid() {
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            Encoding::Hex.decode("abc", &mut buf),
+            Err(B64Error::InvalidLength)
+        );
+        assert_eq!(
+            Encoding::Hex.decode("0g", &mut buf),
+            Err(B64Error::InvalidEncoding)
+        );
+        assert_eq!(
+            Encoding::Hex.decode("0011", &mut [0u8; 1]),
+            Err(B64Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn b64_decoder_chunks() {
+        let encoded = "AQIDBAUGBwgJCgsMDQ4PEBESEw";
+        let mut expected = [0u8; 32];
+        let expected = Encoding::B64.decode(encoded, &mut expected).unwrap();
+
+        // Feed the input in every possible pair of split points
+        for i in 0..=encoded.len() {
+            for j in i..=encoded.len() {
+                let mut decoder = B64Decoder::new();
+                let mut out = [0u8; 32];
+                let mut len = 0;
+
+                for chunk in &[&encoded[..i], &encoded[i..j], &encoded[j..]] {
+                    len += decoder.update(chunk, &mut out[len..]).unwrap();
+                }
+
+                len += decoder.finalize(&mut out[len..]).unwrap();
+                assert_eq!(&out[..len], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn b64_decoder_empty() {
+        let mut decoder = B64Decoder::new();
+        assert_eq!(decoder.update("", &mut []), Ok(0));
+        assert_eq!(decoder.finalize(&mut []), Ok(0));
+        assert_eq!(B64Decoder::new().finalize(&mut []), Ok(0));
+    }
+
+    #[test]
+    fn b64_decoder_errors() {
+        let mut decoder = B64Decoder::new();
+        let mut out = [0u8; 8];
+        assert_eq!(decoder.update("AQIDB", &mut out), Ok(3));
+        assert_eq!(
+            decoder.finalize(&mut out).err(),
+            Encoding::B64.decode("AQIDB", &mut out).err()
+        );
+
+        let mut decoder = B64Decoder::new();
+        assert_eq!(
+            decoder.update("AQ;D", &mut out),
+            Err(B64Error::InvalidEncoding)
+        );
+
+        let mut decoder = B64Decoder::new();
+        assert_eq!(
+            decoder.update("AQIDBAUG", &mut out[..4]),
+            Err(B64Error::InvalidLength)
+        );
+        assert_eq!(B64Decoder::new().finalize(&mut []), Ok(0));
+    }
 }
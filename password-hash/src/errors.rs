@@ -99,10 +99,22 @@ impl fmt::Display for Error {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+// `core::error::Error` was only stabilized in Rust 1.81, which is above this
+// crate's MSRV, so by default the error types implement the trait only when
+// `std` is enabled. The opt-in `core-error` feature implements it without
+// `std`, raising the MSRV to 1.81. `B64Error` is a re-export of
+// `base64ct::Error`, which only implements the trait with `std`, so it's
+// only reported as a source then.
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
+            #[cfg(feature = "std")]
             Self::B64Encoding(err) => Some(err),
             Self::ParamValueInvalid(err) => Some(err),
             Self::SaltInvalid(err) => Some(err),
@@ -136,14 +148,53 @@ pub enum InvalidValue {
     /// Value is malformed.
     Malformed,
 
-    /// Value exceeds the maximum allowed length.
+    /// Value exceeds the maximum allowed magnitude, i.e. a decimal which
+    /// overflows the requested integer type.
+    ///
+    /// Values which exceed a maximum length were previously reported as
+    /// `TooLong` too, but are now reported as
+    /// [`InvalidValue::MaxLengthExceeded`], which includes the limit. Use
+    /// [`InvalidValue::is_too_long`] to match either.
     TooLong,
 
+    /// Value exceeds the maximum allowed length (in ASCII characters, i.e.
+    /// bytes) for the field it was parsed as.
+    #[non_exhaustive]
+    MaxLengthExceeded {
+        /// Maximum allowed length.
+        max: usize,
+
+        /// Actual length of the value.
+        actual: usize,
+    },
+
     /// Value does not satisfy the minimum length.
     TooShort,
+
+    /// Value is outside of the range permitted for the parameter.
+    OutOfRange,
 }
 
 impl InvalidValue {
+    /// Create the error for a field of `actual` bytes whose maximum length
+    /// is `max`, i.e. [`InvalidValue::MaxLengthExceeded`].
+    ///
+    /// This is how all of the field types in this crate report overlong
+    /// input, and is intended for use by downstream field types too, so they
+    /// report it identically. The lengths are stored as given.
+    pub const fn too_long(max: usize, actual: usize) -> Self {
+        Self::MaxLengthExceeded { max, actual }
+    }
+
+    /// Is this [`InvalidValue::TooLong`] or [`InvalidValue::MaxLengthExceeded`]?
+    ///
+    /// This matches every error which was reported as `TooLong` before the
+    /// length context was added, so it can be used in place of matching
+    /// `TooLong` directly.
+    pub const fn is_too_long(&self) -> bool {
+        matches!(self, Self::TooLong | Self::MaxLengthExceeded { .. })
+    }
+
     /// Create an [`Error::ParamValueInvalid`] which warps this error.
     pub fn param_error(self) -> Error {
         Error::ParamValueInvalid(self)
@@ -162,10 +213,14 @@ impl fmt::Display for InvalidValue {
             Self::InvalidFormat => f.write_str("value format is invalid"),
             Self::Malformed => f.write_str("value malformed"),
             Self::TooLong => f.write_str("value to long"),
+            Self::MaxLengthExceeded { max, actual } => {
+                write!(f, "value is {} bytes, maximum is {}", actual, max)
+            }
             Self::TooShort => f.write_str("value to short"),
+            Self::OutOfRange => f.write_str("value out of range"),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for InvalidValue {}
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for InvalidValue {}
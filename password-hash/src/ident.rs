@@ -16,7 +16,7 @@
 //!
 //! [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
 
-use crate::{Error, Result};
+use crate::{value::check_len, Error, Result};
 use core::{fmt, ops::Deref, str};
 
 /// Algorithm or parameter identifier.
@@ -52,22 +52,21 @@ impl<'a> Ident<'a> {
     pub const fn new(s: &'a str) -> Result<Self> {
         let input = s.as_bytes();
 
-        match input.len() {
-            1..=Self::MAX_LENGTH => {
-                let mut i = 0;
-
-                while i < input.len() {
-                    if !matches!(input[i], b'a'..=b'z' | b'0'..=b'9' | b'-') {
-                        return Err(Error::ParamNameInvalid);
-                    }
+        if input.is_empty() || check_len(input.len(), Self::MAX_LENGTH).is_err() {
+            return Err(Error::ParamNameInvalid);
+        }
 
-                    i += 1;
-                }
+        let mut i = 0;
 
-                Ok(Self(s))
+        while i < input.len() {
+            if !matches!(input[i], b'a'..=b'z' | b'0'..=b'9' | b'-') {
+                return Err(Error::ParamNameInvalid);
             }
-            _ => Err(Error::ParamNameInvalid),
+
+            i += 1;
         }
+
+        Ok(Self(s))
     }
 
     /// Parse an [`Ident`] from a string, panicking on parse errors.
@@ -28,27 +28,37 @@ extern crate std;
 #[cfg(feature = "rand_core")]
 pub use rand_core;
 
+pub mod decimal;
 pub mod errors;
+pub mod params;
 
 mod encoding;
 mod ident;
 mod output;
-mod params;
 mod salt;
 mod traits;
 mod value;
 
+#[cfg(test)]
+mod test_util;
+
 pub use crate::{
-    encoding::Encoding,
+    decimal::{Decimal, FromDecimal},
+    encoding::{B64Decoder, Encoding, ValueEncoding},
     errors::{Error, Result},
     ident::Ident,
     output::Output,
-    params::ParamsString,
+    params::{DataParam, NamedValue, Params, ParamsBuilder, ParamsString},
     salt::{Salt, SaltString},
     traits::{McfHasher, PasswordHasher, PasswordVerifier},
-    value::{Decimal, Value},
+    value::{NormalizedValue, Value, ValueBuf, ValueKind, ValueWriter},
 };
 
+#[cfg(feature = "zeroize")]
+pub use crate::value::SecretValue;
+#[cfg(feature = "alloc")]
+pub use crate::value::ValueInterner;
+
 use core::fmt::{self, Debug};
 
 #[cfg(feature = "alloc")]
@@ -1,10 +1,7 @@
 //! Algorithm parameters.
 
-use crate::errors::InvalidValue;
-use crate::{
-    value::{Decimal, Value},
-    Encoding, Error, Ident, Result,
-};
+use crate::errors::{B64Error, InvalidValue};
+use crate::{Decimal, Encoding, Error, Ident, Result, Value};
 use core::{
     fmt::{self, Debug, Write},
     iter::FromIterator,
@@ -23,10 +20,301 @@ pub(crate) const PARAMS_DELIMITER: char = ',';
 /// Maximum number of supported parameters.
 const MAX_LENGTH: usize = 127;
 
+/// Maximum number of pairs yielded by [`parse_list`], i.e. the most which
+/// fit in a [`ParamsString`] (each taking at least 4 bytes, e.g. `a=1,`).
+const MAX_PAIRS: usize = (MAX_LENGTH + 1) / 4;
+
 /// Error message used with `expect` for when internal invariants are violated
 /// (i.e. the contents of a [`ParamsString`] should always be valid)
 const INVARIANT_VIOLATED_MSG: &str = "PHC params invariant violated";
 
+/// Parse a `<param>=<value>(,<param>=<value>)*` parameter list, yielding
+/// each validated name/value pair in turn.
+///
+/// Unlike [`Params::new`], this reports errors per pair as it goes:
+///
+/// - [`Error::ParamsMaxExceeded`] for a pair beyond the most which fit in a
+///   [`ParamsString`] (32), which bounds the names kept for checking for
+///   duplicates
+/// - [`Error::ParamValueInvalid`] with [`InvalidValue::Malformed`] for a
+///   pair which is missing `=` (or has more than one)
+/// - [`Error::ParamNameInvalid`] for an empty or otherwise invalid name
+/// - [`Error::ParamNameDuplicated`] for a name which has already occurred
+/// - [`Error::ParamValueInvalid`] for an invalid value
+///
+/// Iteration stops after the first error. An empty input yields no pairs.
+pub fn parse_list(input: &str) -> impl Iterator<Item = Result<Pair<'_>>> {
+    ParseList {
+        input,
+        pos: if input.is_empty() { None } else { Some(0) },
+        names: [""; MAX_PAIRS],
+        count: 0,
+    }
+}
+
+/// Iterator returned by [`parse_list`].
+struct ParseList<'a> {
+    /// Complete input string.
+    input: &'a str,
+
+    /// Offset of the next pair to parse, or `None` if finished.
+    pos: Option<usize>,
+
+    /// Names of the pairs yielded so far.
+    names: [&'a str; MAX_PAIRS],
+
+    /// Number of pairs yielded so far.
+    count: usize,
+}
+
+impl<'a> ParseList<'a> {
+    /// Parse a single pair, checking it against the pairs yielded so far.
+    fn parse_pair(&self, param: &'a str) -> Result<Pair<'a>> {
+        if self.count == MAX_PAIRS {
+            return Err(Error::ParamsMaxExceeded);
+        }
+
+        let (name, value) = param
+            .split_once(PAIR_DELIMITER)
+            .ok_or(Error::ParamValueInvalid(InvalidValue::Malformed))?;
+
+        let name = Ident::try_from(name)?;
+
+        if value.contains(PAIR_DELIMITER) {
+            return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
+        }
+
+        let value = Value::try_from(value)?;
+
+        if self.names[..self.count].contains(&name.as_str()) {
+            return Err(Error::ParamNameDuplicated);
+        }
+
+        Ok((name, value))
+    }
+}
+
+impl<'a> Iterator for ParseList<'a> {
+    type Item = Result<Pair<'a>>;
+
+    fn next(&mut self) -> Option<Result<Pair<'a>>> {
+        let pos = self.pos?;
+        let remaining = &self.input[pos..];
+
+        let param = match remaining.find(PARAMS_DELIMITER) {
+            Some(end) => {
+                self.pos = Some(pos + end + 1);
+                &remaining[..end]
+            }
+            None => {
+                self.pos = None;
+                remaining
+            }
+        };
+
+        let result = self.parse_pair(param);
+
+        match result {
+            Ok((name, _)) => {
+                self.names[self.count] = name.as_str();
+                self.count += 1;
+            }
+            Err(_) => self.pos = None,
+        }
+
+        Some(result)
+    }
+}
+
+/// Parameter value along with the name of the parameter it belongs to.
+///
+/// This is the borrowed equivalent of a [`Pair`] with named accessors, and
+/// is useful for keeping track of which parameter a value came from, e.g.
+/// for error reporting. Its [`Display`][`fmt::Display`] impl renders
+/// `<param>=<value>`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NamedValue<'a> {
+    name: Ident<'a>,
+    value: Value<'a>,
+}
+
+impl<'a> NamedValue<'a> {
+    /// Create a new [`NamedValue`] from the given parameter name and value.
+    pub fn new(name: Ident<'a>, value: Value<'a>) -> Self {
+        Self { name, value }
+    }
+
+    /// Name of the parameter.
+    pub fn name(&self) -> Ident<'a> {
+        self.name
+    }
+
+    /// Value of the parameter.
+    pub fn value(&self) -> Value<'a> {
+        self.value
+    }
+}
+
+impl<'a> From<Pair<'a>> for NamedValue<'a> {
+    fn from((name, value): Pair<'a>) -> Self {
+        Self::new(name, value)
+    }
+}
+
+impl<'a> From<NamedValue<'a>> for Pair<'a> {
+    fn from(named: NamedValue<'a>) -> Self {
+        (named.name, named.value)
+    }
+}
+
+impl<'a> fmt::Display for NamedValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.name, PAIR_DELIMITER, self.value)
+    }
+}
+
+/// Value of the `data` parameter of the [Argon2 Encoding][1].
+///
+/// The specification defines this as binary data of at most
+/// [`DataParam::MAX_DECODED_LEN`] bytes, B64-encoded. This type enforces
+/// both of those constraints on construction, so it only needs to be
+/// decoded into a sufficiently large buffer.
+///
+/// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DataParam<'a>(Value<'a>);
+
+impl<'a> DataParam<'a> {
+    /// Maximum length of the decoded data in bytes.
+    pub const MAX_DECODED_LEN: usize = 32;
+
+    /// Maximum length of the B64-encoded data in ASCII characters.
+    pub const MAX_LENGTH: usize = Encoding::B64.encoded_len_for(Self::MAX_DECODED_LEN);
+
+    /// Validate the given [`Value`] as an Argon2 `data` parameter.
+    ///
+    /// Returns [`InvalidValue::MaxLengthExceeded`] if it would decode to
+    /// more than [`DataParam::MAX_DECODED_LEN`] bytes, or
+    /// [`Error::B64Encoding`] if it isn't valid B64.
+    pub fn new(value: Value<'a>) -> Result<Self> {
+        if value.len() > Self::MAX_LENGTH {
+            return Err(InvalidValue::too_long(Self::MAX_LENGTH, value.len()).param_error());
+        }
+
+        // Decode with the same call as `DataParam::decode` (the value is
+        // short enough to fit in a scratch buffer), so that construction
+        // fails whenever decoding would
+        Encoding::B64.decode(value.as_str(), &mut [0u8; Self::MAX_DECODED_LEN])?;
+        Ok(Self(value))
+    }
+
+    /// Decode the data into the provided buffer, returning the decoded
+    /// bytes.
+    ///
+    /// This can only fail if `buf` is too small. A buffer of
+    /// [`DataParam::MAX_DECODED_LEN`] bytes is always sufficient.
+    pub fn decode<'b>(&self, buf: &'b mut [u8]) -> core::result::Result<&'b [u8], B64Error> {
+        Encoding::B64.decode(self.0.as_str(), buf)
+    }
+
+    /// Borrow the encoded data as a [`Value`].
+    pub fn as_value(&self) -> Value<'a> {
+        self.0
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for DataParam<'a> {
+    type Error = Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+/// Borrowed algorithm parameter string.
+///
+/// This type is a zero-copy view of a validated set of PHC parameters, e.g.
+/// a `<param>=<value>(,<param>=<value>)*` field sliced out of a larger PHC
+/// string. Unlike [`ParamsString`] it borrows the input rather than copying
+/// it into a fixed-size buffer, and as such it isn't subject to the
+/// [`ParamsString`] length limit.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct Params<'a>(&'a str);
+
+impl<'a> Params<'a> {
+    /// Parse [`Params`] from the provided `str`, validating each of the
+    /// name/value pairs it contains.
+    pub fn new(s: &'a str) -> Result<Self> {
+        if s.is_empty() {
+            return Ok(Self(s));
+        }
+
+        for mut param in s.split(PARAMS_DELIMITER).map(|p| p.split(PAIR_DELIMITER)) {
+            // Validate name
+            param
+                .next()
+                .ok_or(Error::ParamNameInvalid)
+                .and_then(Ident::try_from)?;
+
+            // Validate value
+            param
+                .next()
+                .ok_or(Error::ParamValueInvalid(InvalidValue::Malformed))
+                .and_then(Value::try_from)?;
+
+            if param.next().is_some() {
+                return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
+            }
+        }
+
+        Ok(Self(s))
+    }
+
+    /// Borrow the contents of these [`Params`] as a `str`.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Is this set of parameters empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the parameters, borrowing from the original input.
+    pub fn iter(&self) -> Iter<'a> {
+        Iter::new(self.0)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Params<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl<'a> IntoIterator for Params<'a> {
+    type Item = Pair<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> fmt::Display for Params<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'a> fmt::Debug for Params<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
 /// Algorithm parameter string.
 ///
 /// The [PHC string format specification][1] defines a set of optional
@@ -170,43 +458,86 @@ impl ParamsString {
     }
 }
 
-impl FromStr for ParamsString {
-    type Err = Error;
+/// Builder for a [`ParamsString`].
+///
+/// Parameters are emitted in the order they are added, and decimals are
+/// written in the canonical PHC decimal encoding (i.e. without leading
+/// zeroes). The same rules as the `ParamsString::add_*` methods apply, e.g.
+/// duplicate names are rejected, but errors are deferred until
+/// [`ParamsBuilder::build`] so calls can be chained.
+///
+/// ```
+/// use password_hash::{ParamsBuilder, Value};
+///
+/// let params = ParamsBuilder::new()
+///     .decimal("m", 4096)
+///     .decimal("t", 3)
+///     .value("keyid", Value::new("abc").unwrap())
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(params.as_str(), "m=4096,t=3,keyid=abc");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ParamsBuilder {
+    /// Parameters added so far.
+    params: ParamsString,
 
-    fn from_str(s: &str) -> Result<Self> {
-        if s.as_bytes().len() > MAX_LENGTH {
-            return Err(Error::ParamsMaxExceeded);
+    /// First error encountered while adding parameters, if any.
+    error: Option<Error>,
+}
+
+impl ParamsBuilder {
+    /// Create a new empty [`ParamsBuilder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter with a decimal value.
+    pub fn decimal<'a>(mut self, name: impl TryInto<Ident<'a>>, value: Decimal) -> Self {
+        if self.error.is_none() {
+            self.error = self.params.add_decimal(name, value).err();
         }
 
-        if s.is_empty() {
-            return Ok(ParamsString::new());
+        self
+    }
+
+    /// Add a parameter with a string value.
+    pub fn value<'a>(mut self, name: impl TryInto<Ident<'a>>, value: Value<'a>) -> Self {
+        if self.error.is_none() {
+            self.error = self.params.add_str(name, value).err();
         }
 
-        // Validate the string is well-formed
-        for mut param in s.split(PARAMS_DELIMITER).map(|p| p.split(PAIR_DELIMITER)) {
-            // Validate name
-            param
-                .next()
-                .ok_or(Error::ParamNameInvalid)
-                .and_then(Ident::try_from)?;
+        self
+    }
 
-            // Validate value
-            param
-                .next()
-                .ok_or(Error::ParamValueInvalid(InvalidValue::Malformed))
-                .and_then(Value::try_from)?;
+    /// Finish building, returning the resulting [`ParamsString`], or the
+    /// first error encountered while adding parameters.
+    pub fn build(self) -> Result<ParamsString> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.params),
+        }
+    }
+}
 
-            if param.next().is_some() {
-                return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
-            }
+impl FromStr for ParamsString {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() > MAX_LENGTH {
+            return Err(Error::ParamsMaxExceeded);
         }
 
+        // Validate the string is well-formed
+        Params::new(s)?;
+
         let mut bytes = [0u8; MAX_LENGTH];
-        bytes[..s.as_bytes().len()].copy_from_slice(s.as_bytes());
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
 
         Ok(Self(Buffer {
             bytes,
-            length: s.as_bytes().len() as u8,
+            length: s.len() as u8,
         }))
     }
 }
@@ -238,20 +569,21 @@ impl fmt::Debug for ParamsString {
     }
 }
 
-/// Iterator over algorithm parameters stored in a [`ParamsString`] struct.
+/// Iterator over algorithm parameters stored in a [`Params`] or
+/// [`ParamsString`] struct.
+#[derive(Clone, Debug)]
 pub struct Iter<'a> {
-    inner: Option<str::Split<'a, char>>,
+    /// Remaining (unparsed) parameters, or `None` if exhausted.
+    remaining: Option<&'a str>,
 }
 
 impl<'a> Iter<'a> {
     /// Create a new [`Iter`].
     fn new(s: &'a str) -> Self {
         if s.is_empty() {
-            Self { inner: None }
+            Self { remaining: None }
         } else {
-            Self {
-                inner: Some(s.split(PARAMS_DELIMITER)),
-            }
+            Self { remaining: Some(s) }
         }
     }
 }
@@ -260,7 +592,20 @@ impl<'a> Iterator for Iter<'a> {
     type Item = Pair<'a>;
 
     fn next(&mut self) -> Option<Pair<'a>> {
-        let mut param = self.inner.as_mut()?.next()?.split(PAIR_DELIMITER);
+        let remaining = self.remaining?;
+
+        let param = match remaining.split_once(PARAMS_DELIMITER) {
+            Some((param, rest)) => {
+                self.remaining = Some(rest);
+                param
+            }
+            None => {
+                self.remaining = None;
+                remaining
+            }
+        };
+
+        let mut param = param.split(PAIR_DELIMITER);
 
         let name = param
             .next()
@@ -275,8 +620,19 @@ impl<'a> Iterator for Iter<'a> {
         debug_assert_eq!(param.next(), None);
         Some((name, value))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self
+            .remaining
+            .map(|s| s.matches(PARAMS_DELIMITER).count() + 1)
+            .unwrap_or(0);
+
+        (len, Some(len))
+    }
 }
 
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
 /// Parameter buffer.
 #[derive(Clone, Debug, Eq)]
 struct Buffer {
@@ -327,7 +683,10 @@ impl Write for Buffer {
 
 #[cfg(test)]
 mod tests {
-    use super::{Error, FromIterator, Ident, ParamsString, Value};
+    use super::{
+        parse_list, str, B64Error, DataParam, Error, FromIterator, Ident, InvalidValue, NamedValue,
+        Pair, Params, ParamsBuilder, ParamsString, Value, MAX_LENGTH, MAX_PAIRS,
+    };
 
     #[cfg(feature = "alloc")]
     use alloc::string::ToString;
@@ -356,6 +715,29 @@ mod tests {
         assert_eq!(params.to_string(), "a=AQ,b=AgM,c=BAUG");
     }
 
+    #[test]
+    fn builder() {
+        let params = ParamsBuilder::new()
+            .decimal("a", 1)
+            .value(Ident::new_unwrap("b"), Value::new("x.y").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(params.as_str(), "a=1,b=x.y");
+        assert_eq!(params, "a=1,b=x.y".parse().unwrap());
+
+        let err = ParamsBuilder::new()
+            .decimal("a", 1)
+            .decimal("a", 2)
+            .decimal("b", 3)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::ParamNameDuplicated);
+
+        let err = ParamsBuilder::new().decimal("a;", 1).build().unwrap_err();
+        assert_eq!(err, Error::ParamNameInvalid);
+    }
+
     #[test]
     fn duplicate_names() {
         let name = Ident::new("a").unwrap();
@@ -402,10 +784,158 @@ mod tests {
         assert_eq!(i.next(), None);
     }
 
+    #[test]
+    fn borrowed_iter() {
+        let input = "a=1,b=2,c=3";
+        let params = Params::new(input).unwrap();
+
+        let mut i = params.iter();
+        assert_eq!(i.len(), 3);
+
+        let (name, value) = i.next().unwrap();
+        assert_eq!(name.as_str(), "a");
+        assert_eq!(value.as_str(), "1");
+        assert_eq!(value.as_str().as_ptr(), input[2..].as_ptr());
+        assert_eq!(i.len(), 2);
+
+        let rest = i.clone();
+        assert_eq!(i.count(), 2);
+        assert_eq!(rest.len(), 2);
+
+        assert_eq!(Params::new("").unwrap().iter().len(), 0);
+    }
+
+    #[test]
+    fn borrowed_reject_malformed() {
+        assert_eq!(
+            Params::new("a=1,b").err().unwrap(),
+            Error::ParamValueInvalid(InvalidValue::Malformed)
+        );
+        assert_eq!(Params::new("A=1").err().unwrap(), Error::ParamNameInvalid);
+    }
+
     //
     // `FromStr` tests
     //
 
+    #[test]
+    fn parse_list_valid() {
+        let mut pairs = parse_list("m=65536,t=3,p=4");
+        for &(name, value) in &[("m", "65536"), ("t", "3"), ("p", "4")] {
+            let (n, v) = pairs.next().unwrap().unwrap();
+            assert_eq!(n, Ident::new_unwrap(name));
+            assert_eq!(v, value);
+        }
+        assert!(pairs.next().is_none());
+        assert!(parse_list("").next().is_none());
+    }
+
+    #[test]
+    fn named_value() {
+        let (name, value) = parse_list("m=65536").next().unwrap().unwrap();
+        let named = NamedValue::from((name, value));
+        assert_eq!(named.name(), Ident::new_unwrap("m"));
+        assert_eq!(named.value(), "65536");
+        assert_eq!(Pair::from(named), (name, value));
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(named.to_string(), "m=65536");
+    }
+
+    #[test]
+    fn data_param() {
+        assert_eq!(DataParam::MAX_LENGTH, 43);
+
+        let data = DataParam::new(Value::new("Dw8PDw8P").unwrap()).unwrap();
+        let mut buf = [0u8; DataParam::MAX_DECODED_LEN];
+        assert_eq!(data.decode(&mut buf).unwrap(), [0x0f; 6]);
+        assert_eq!(data.as_value(), "Dw8PDw8P");
+        assert!(data.decode(&mut [0u8; 5]).is_err());
+
+        let encoded = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let max = Value::new(&encoded[..43]).unwrap();
+        assert_eq!(
+            DataParam::new(max).unwrap().decode(&mut buf).unwrap().len(),
+            32
+        );
+
+        let too_long = Value::new(encoded).unwrap();
+        assert_eq!(
+            DataParam::try_from(too_long),
+            Err(InvalidValue::too_long(43, 44).param_error())
+        );
+        assert!(matches!(
+            DataParam::new(Value::new("A").unwrap()),
+            Err(Error::B64Encoding(_))
+        ));
+
+        // Non-zero trailing bits: rejected on construction, rather than
+        // accepted and then failing to decode
+        let ab = Value::new("AB").unwrap();
+        assert_eq!(
+            DataParam::new(ab),
+            Err(Error::B64Encoding(B64Error::InvalidEncoding))
+        );
+        assert_eq!(
+            ab.b64_decode(&mut buf),
+            Err(Error::B64Encoding(B64Error::InvalidEncoding))
+        );
+    }
+
+    #[test]
+    fn parse_list_errors() {
+        for &(input, err) in &[
+            ("a=1,b", Error::ParamValueInvalid(InvalidValue::Malformed)),
+            (
+                "a=1,b=2=3",
+                Error::ParamValueInvalid(InvalidValue::Malformed),
+            ),
+            ("a=1,=2", Error::ParamNameInvalid),
+            ("a=1,", Error::ParamValueInvalid(InvalidValue::Malformed)),
+            ("a=1,b=2,a=3", Error::ParamNameDuplicated),
+            (
+                "a=1,b=x;y",
+                Error::ParamValueInvalid(InvalidValue::InvalidChar(';')),
+            ),
+        ] {
+            let mut pairs = parse_list(input);
+            assert!(pairs.next().unwrap().is_ok());
+            let result = pairs.by_ref().find_map(|r| r.err());
+            assert_eq!(result, Some(err), "{}", input);
+            assert!(pairs.next().is_none());
+        }
+    }
+
+    #[test]
+    fn parse_list_max_pairs() {
+        assert_eq!(MAX_PAIRS, 32);
+
+        // Longer than a `ParamsString`, but accepted by `Params::new`
+        let mut buf = [0u8; 256];
+        let mut len = 0;
+
+        for i in 0..MAX_PAIRS + 1 {
+            let pair = [b'a' + (i / 26) as u8, b'a' + (i % 26) as u8];
+            buf[len..][..2].copy_from_slice(&pair);
+            buf[len + 2..][..3].copy_from_slice(b"=1,");
+            len += 5;
+        }
+
+        let input = str::from_utf8(&buf[..len - 1]).unwrap();
+        let (max, _) = input.split_at(MAX_PAIRS * 5 - 1);
+        assert!(max.len() > MAX_LENGTH);
+        assert!(Params::new(max).is_ok());
+        assert_eq!(parse_list(max).map(Result::unwrap).count(), MAX_PAIRS);
+
+        let mut pairs = parse_list(input);
+        assert_eq!(
+            pairs.by_ref().take(MAX_PAIRS).filter(Result::is_ok).count(),
+            MAX_PAIRS
+        );
+        assert_eq!(pairs.next(), Some(Err(Error::ParamsMaxExceeded)));
+        assert!(pairs.next().is_none());
+    }
+
     #[test]
     fn parse_empty() {
         let params = ParamsString::from_str("").unwrap();
@@ -3,7 +3,7 @@
 use crate::{Encoding, Error, Result, Value};
 use core::{fmt, str};
 
-use crate::errors::InvalidValue;
+use crate::{errors::InvalidValue, value::check_len};
 #[cfg(feature = "rand_core")]
 use rand_core::CryptoRngCore;
 
@@ -103,15 +103,13 @@ impl<'a> Salt<'a> {
     /// Create a [`Salt`] from the given B64-encoded input string, validating
     /// [`Salt::MIN_LENGTH`] and [`Salt::MAX_LENGTH`] restrictions.
     pub fn from_b64(input: &'a str) -> Result<Self> {
-        let length = input.as_bytes().len();
+        let length = input.len();
 
         if length < Self::MIN_LENGTH {
             return Err(Error::SaltInvalid(InvalidValue::TooShort));
         }
 
-        if length > Self::MAX_LENGTH {
-            return Err(Error::SaltInvalid(InvalidValue::TooLong));
-        }
+        check_len(length, Self::MAX_LENGTH).map_err(InvalidValue::salt_error)?;
 
         // TODO(tarcieri): full B64 decoding check?
         for char in input.chars() {
@@ -177,6 +175,12 @@ impl<'a> TryFrom<&'a str> for Salt<'a> {
     }
 }
 
+impl<'a> From<Salt<'a>> for Value<'a> {
+    fn from(salt: Salt<'a>) -> Value<'a> {
+        salt.0
+    }
+}
+
 impl<'a> fmt::Display for Salt<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.as_str())
@@ -215,7 +219,7 @@ impl SaltString {
         // Assert `s` parses successfully as a `Salt`
         Salt::from_b64(s)?;
 
-        let len = s.as_bytes().len();
+        let len = s.len();
 
         let mut bytes = [0u8; Salt::MAX_LENGTH];
         bytes[..len].copy_from_slice(s.as_bytes());
@@ -342,7 +346,13 @@ mod tests {
     fn reject_new_too_long() {
         let s = "01234567891123456789212345678931234567894123456785234567896234567";
         let err = Salt::from_b64(s).err().unwrap();
-        assert_eq!(err, Error::SaltInvalid(InvalidValue::TooLong));
+        assert_eq!(
+            err,
+            Error::SaltInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
     }
 
     #[test]
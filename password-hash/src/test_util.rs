@@ -0,0 +1,35 @@
+//! Helpers for the unit tests.
+
+/// Linear congruential generator, so generated inputs are arbitrary but
+/// deterministic.
+///
+/// This is **not** suitable for anything other than generating test inputs.
+#[derive(Clone, Debug)]
+pub struct Lcg(u32);
+
+impl Lcg {
+    /// Seed used by tests which don't need a particular one.
+    pub const DEFAULT_SEED: u32 = 0x2545_f491;
+
+    /// Create a generator with the given seed.
+    pub const fn new(seed: u32) -> Self {
+        Self(seed)
+    }
+
+    /// Advance the generator, returning its new state.
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        self.0
+    }
+
+    /// Generate a byte from the high (i.e. most random) bits of the state.
+    pub fn next_byte(&mut self) -> u8 {
+        (self.next_u32() >> 24) as u8
+    }
+}
+
+impl Default for Lcg {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
@@ -13,12 +13,52 @@
 //!
 //! [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
 
-use crate::errors::InvalidValue;
-use crate::{Encoding, Error, Result};
-use core::{fmt, str};
+use crate::decimal::{self, Decimal, FromDecimal};
+use crate::errors::{B64Error, InvalidValue};
+use crate::{Encoding, Error, Result, ValueEncoding};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    ops::{Range, RangeInclusive},
+    str,
+};
+use subtle::{Choice, ConstantTimeEq};
 
-/// Type used to represent decimal (i.e. integer) values.
-pub type Decimal = u32;
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::BTreeSet, vec, vec::Vec};
+
+/// Error message used with `expect` for when internal invariants are violated
+/// (i.e. the contents of a [`Value`] or [`ValueBuf`] should always be valid)
+const INVARIANT_VIOLATED_MSG: &str = "value string invariant violated";
+
+/// Construct a [`Value<'static>`][`Value`] from a string literal, validated
+/// at compile time.
+///
+/// Invalid or overlong literals are a compile error rather than a runtime
+/// panic. This is implemented in terms of [`Value::new_const`].
+///
+/// ```
+/// use password_hash::{value, Value};
+///
+/// const COST: Value<'static> = value!("32");
+/// assert_eq!(COST.decimal().unwrap(), 32);
+/// ```
+///
+/// ```compile_fail
+/// use password_hash::{value, Value};
+///
+/// const INVALID: Value<'static> = value!("x;y");
+/// ```
+#[macro_export]
+macro_rules! value {
+    ($s:expr) => {{
+        const VALUE: $crate::Value<'static> = $crate::Value::new_const($s);
+        VALUE
+    }};
+}
 
 /// Algorithm parameter value string.
 ///
@@ -33,7 +73,14 @@ pub type Decimal = u32;
 /// # Additional Notes
 /// The PHC spec allows for algorithm-defined maximum lengths for parameter
 /// values, however this library defines a [`Value::MAX_LENGTH`] of 64 ASCII
-/// characters.
+/// characters by default. Algorithms which need a different maximum can use
+/// [`Value::new_with_max`].
+///
+/// # Constant-time comparisons
+/// The [`PartialEq`] impl for [`Value`] is variable-time, which is fine for
+/// parsing logic. Values which may contain secret material (e.g. the
+/// `keyid` or `data` parameters of the [Argon2 Encoding][2]) should instead
+/// be compared using the [`ConstantTimeEq`] impl.
 ///
 /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
 /// [2]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
@@ -47,11 +94,59 @@ impl<'a> Value<'a> {
     /// as this library internally uses this type to represent salts.
     pub const MAX_LENGTH: usize = 64;
 
+    /// The empty [`Value`], which is permitted by the PHC string format.
+    ///
+    /// This is also the [`Default`] value, and is equal to any other empty
+    /// value, e.g. `Value::new("")`. It allows distinguishing a parameter
+    /// which is present but empty from one which is absent (`None`).
+    pub const EMPTY: Value<'static> = Value("");
+
+    /// The set of characters allowed in a [`Value`]: `[a-zA-Z0-9/+.-]`.
+    pub const VALID_CHARS: &'static str =
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789/+.-";
+
+    /// Is the given character allowed in a [`Value`]?
+    ///
+    /// This is the same check performed by [`Value::new`], and is consistent
+    /// with [`Value::VALID_CHARS`].
+    pub const fn is_valid_char(c: char) -> bool {
+        is_char_valid(c)
+    }
+
     /// Parse a [`Value`] from the provided `str`, validating it according to
     /// the PHC string format's rules.
     pub fn new(input: &'a str) -> Result<Self> {
-        if input.as_bytes().len() > Self::MAX_LENGTH {
-            return Err(Error::ParamValueInvalid(InvalidValue::TooLong));
+        Self::new_with_max(input, Self::MAX_LENGTH)
+    }
+
+    /// Parse a [`Value`] from the provided `str`, validating it according to
+    /// the PHC string format's rules and the provided algorithm-specific
+    /// maximum length (in ASCII characters).
+    ///
+    /// This is intended for algorithms whose specification defines a
+    /// maximum parameter value length other than [`Value::MAX_LENGTH`].
+    pub fn new_with_max(input: &'a str, max_len: usize) -> Result<Self> {
+        Self::new_located_with_max(input, max_len).map_err(|(err, _)| err)
+    }
+
+    /// Parse a [`Value`] from the provided `str` like [`Value::new`], but on
+    /// failure also return the byte offset at which the error occurred.
+    ///
+    /// For invalid characters this is the offset of the first invalid
+    /// character, and for values which are too long it is the offset at
+    /// which [`Value::MAX_LENGTH`] was exceeded.
+    pub fn new_located(input: &'a str) -> core::result::Result<Self, (Error, usize)> {
+        Self::new_located_with_max(input, Self::MAX_LENGTH)
+    }
+
+    /// Parse a [`Value`] with the given maximum length, returning the byte
+    /// offset of the error on failure.
+    fn new_located_with_max(
+        input: &'a str,
+        max_len: usize,
+    ) -> core::result::Result<Self, (Error, usize)> {
+        if let Err(err) = check_len(input.len(), max_len) {
+            return Err((err.param_error(), max_len));
         }
 
         // Check that the characters are permitted in a PHC parameter value.
@@ -59,246 +154,3483 @@ impl<'a> Value<'a> {
         Ok(Self(input))
     }
 
-    /// Attempt to decode a B64-encoded [`Value`], writing the decoded
-    /// result into the provided buffer, and returning a slice of the buffer
-    /// containing the decoded result on success.
-    ///
-    /// Examples of "B64"-encoded parameters in practice are the `keyid` and
-    /// `data` parameters used by the [Argon2 Encoding][1] as described in the
-    /// PHC string format specification.
+    /// Parse a [`Value`] from the provided `str` like [`Value::new`], first
+    /// removing a single pair of matching `"` or `'` quotes if present.
     ///
-    /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
-    pub fn b64_decode<'b>(&self, buf: &'b mut [u8]) -> Result<&'b [u8]> {
-        Ok(Encoding::B64.decode(self.as_str(), buf)?)
+    /// This is a convenience for transports which quote embedded values;
+    /// [`Value::new`] never accepts quotes. A quote at only one end of the
+    /// input (or mismatched quotes) is reported as
+    /// [`InvalidValue::InvalidFormat`].
+    pub fn new_unquoted(input: &'a str) -> Result<Self> {
+        let is_quote = |b: u8| b == b'"' || b == b'\'';
+        let bytes = input.as_bytes();
+
+        let inner = match (bytes.first(), bytes.last()) {
+            (Some(&first), Some(&last)) if is_quote(first) || is_quote(last) => {
+                if bytes.len() < 2 || first != last {
+                    return Err(InvalidValue::InvalidFormat.param_error());
+                }
+
+                &input[1..(input.len() - 1)]
+            }
+            _ => input,
+        };
+
+        Self::new(inner)
     }
 
-    /// Borrow this value as a `str`.
-    pub fn as_str(&self) -> &'a str {
-        self.0
+    /// Parse a [`Value`] from the provided `str`, additionally requiring it
+    /// to be a canonical PHC-encoded decimal.
+    ///
+    /// This applies the decimal encoding rules described in
+    /// [`Value::decimal`] at construction time, including negative decimals,
+    /// so e.g. `"007"` and `"-0"` are rejected. The magnitude of the decimal
+    /// is not restricted.
+    pub fn new_decimal(input: &'a str) -> Result<Self> {
+        let value = Self::new(input)?;
+        decimal::assert_valid_signed(value.as_bytes())?;
+        Ok(value)
     }
 
-    /// Borrow this value as bytes.
-    pub fn as_bytes(&self) -> &'a [u8] {
-        self.as_str().as_bytes()
+    /// Parse a [`Value`] from the provided `str`, validating it as a salt.
+    ///
+    /// This applies the same rules as [`Salt::from_b64`][`crate::Salt::from_b64`],
+    /// including the [`Salt::MIN_LENGTH`][`crate::Salt::MIN_LENGTH`] and
+    /// [`Salt::MAX_LENGTH`][`crate::Salt::MAX_LENGTH`] bounds, and reports
+    /// errors as [`Error::SaltInvalid`].
+    pub fn new_salt(input: &'a str) -> Result<Self> {
+        crate::Salt::from_b64(input).map(Self::from)
     }
 
-    /// Get the length of this value in ASCII characters.
-    pub fn len(&self) -> usize {
-        self.as_str().len()
+    /// Parse a [`Value`] directly from bytes, validating it according to the
+    /// PHC string format's rules.
+    ///
+    /// Checking that each byte is a valid value character also proves the
+    /// input is ASCII (and therefore UTF-8), so this avoids having to
+    /// separately convert the input to a `str` first. Non-ASCII bytes are
+    /// reported as [`InvalidValue::InvalidChar`] containing
+    /// [`char::REPLACEMENT_CHARACTER`], as they don't correspond to a
+    /// character on their own.
+    pub fn from_bytes(input: &'a [u8]) -> Result<Self> {
+        check_len(input.len(), Self::MAX_LENGTH).map_err(InvalidValue::param_error)?;
+
+        for &b in input {
+            if !is_byte_valid(b) {
+                let c = if b.is_ascii() {
+                    char::from(b)
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                };
+
+                return Err(InvalidValue::InvalidChar(c).param_error());
+            }
+        }
+
+        Ok(Self(str::from_utf8(input).expect(INVARIANT_VIOLATED_MSG)))
     }
 
-    /// Is this value empty?
-    pub fn is_empty(&self) -> bool {
-        self.as_str().is_empty()
+    /// Parse each of the given strings as a [`Value`], returning all of them
+    /// on success, or the index and error of the first invalid input.
+    #[cfg(feature = "alloc")]
+    pub fn validate_all<I>(inputs: I) -> core::result::Result<Vec<Self>, (usize, Error)>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, input)| Self::new(input).map_err(|err| (i, err)))
+            .collect()
     }
 
-    /// Attempt to parse this [`Value`] as a PHC-encoded decimal (i.e. integer).
+    /// Parse a [`Value`] from the provided `str` like [`Value::new`], but on
+    /// failure return every error found along with its byte offset, rather
+    /// than only the first.
     ///
-    /// Decimal values are integers which follow the rules given in the
-    /// ["Decimal Encoding" section of the PHC string format specification][1].
+    /// If the input exceeds [`Value::MAX_LENGTH`] that error comes first,
+    /// positioned at the offset where the limit was exceeded (as with
+    /// [`Value::new_located`]), followed by each invalid character in order.
+    #[cfg(feature = "alloc")]
+    pub fn validate_verbose(input: &'a str) -> core::result::Result<Self, Vec<(usize, Error)>> {
+        let mut errors = Vec::new();
+
+        if let Err(err) = check_len(input.len(), Self::MAX_LENGTH) {
+            errors.push((Self::MAX_LENGTH, err.param_error()));
+        }
+
+        for (i, c) in input.char_indices() {
+            if !is_char_valid(c) {
+                errors.push((i, InvalidValue::InvalidChar(c).param_error()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self(input))
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parse a [`Value`] from the provided `'static` string, panicking on
+    /// parse errors.
     ///
-    /// The decimal encoding rules are as follows:
-    /// > For an integer value x, its decimal encoding consist in the following:
-    /// >
-    /// > - If x < 0, then its decimal encoding is the minus sign - followed by the decimal
-    /// >   encoding of -x.
-    /// > - If x = 0, then its decimal encoding is the single character 0.
-    /// > - If x > 0, then its decimal encoding is the smallest sequence of ASCII digits that
-    /// >   matches its value (i.e. there is no leading zero).
-    /// >
-    /// > Thus, a value is a valid decimal for an integer x if and only if all of the following hold true:
-    /// >
-    /// > - The first character is either a - sign, or an ASCII digit.
-    /// > - All characters other than the first are ASCII digits.
-    /// > - If the first character is - sign, then there is at least another character, and the
-    /// >   second character is not a 0.
-    /// > - If the string consists in more than one character, then the first one cannot be a 0.
+    /// This function exists as a workaround for `unwrap` not yet being
+    /// stable in `const fn` contexts, and is intended to allow the result to
+    /// be bound to a constant value, turning an invalid literal into a
+    /// compile-time error.
+    pub const fn new_const(input: &'static str) -> Value<'static> {
+        let bytes = input.as_bytes();
+        assert!(bytes.len() <= Self::MAX_LENGTH, "PHC value string too long");
+
+        let mut i = 0;
+
+        while i < bytes.len() {
+            assert!(
+                is_char_valid(bytes[i] as char),
+                "invalid PHC value character"
+            );
+
+            i += 1;
+        }
+
+        Value(input)
+    }
+
+    /// Parse a [`Value`] from the longest prefix of `input` consisting of
+    /// valid value characters, returning it along with the remaining input.
     ///
-    /// Note: this implementation does not support negative decimals despite
-    /// them being allowed per the spec above. If you need to parse a negative
-    /// number, please parse it from the string representation directly e.g.
-    /// `value.as_str().parse::<i32>()`
+    /// Parsing stops at the first character which isn't allowed in a value,
+    /// e.g. the `,`, `$` or `=` delimiters of a PHC string, so this is
+    /// suitable for building a tokenizer. Returns an error if the prefix is
+    /// longer than [`Value::MAX_LENGTH`].
+    pub fn parse_prefix(input: &'a str) -> Result<(Self, &'a str)> {
+        let len = input
+            .bytes()
+            .position(|b| !is_byte_valid(b))
+            .unwrap_or(input.len());
+
+        // Valid characters are ASCII, so `len` is on a char boundary
+        let (prefix, rest) = input.split_at(len);
+        Ok((Self::new(prefix)?, rest))
+    }
+
+    /// Join the given parts with a separator into a [`Value`] stored in the
+    /// provided buffer, i.e. the inverse of [`Value::split`].
     ///
-    /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#decimal-encoding
-    pub fn decimal(&self) -> Result<Decimal> {
-        let value = self.as_str();
+    /// Each part must be a valid [`Value`], `sep` must be one of
+    /// [`Value::VALID_CHARS`], and the joined result must not exceed
+    /// [`Value::MAX_LENGTH`]. Returns [`Error::OutputSize`] if `buf` is too
+    /// small to hold the result.
+    pub fn join<'b>(parts: &[&str], sep: char, buf: &'b mut [u8]) -> Result<Value<'b>> {
+        if !is_char_valid(sep) {
+            return Err(InvalidValue::InvalidChar(sep).param_error());
+        }
 
-        // Empty strings aren't decimals
-        if value.is_empty() {
-            return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
+        let mut len = parts.len().saturating_sub(1);
+
+        for part in parts {
+            len += Value::new(part)?.len();
         }
 
-        // Ensure all characters are digits
-        for c in value.chars() {
-            if !c.is_ascii_digit() {
-                return Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(c)));
+        check_len(len, Self::MAX_LENGTH).map_err(InvalidValue::param_error)?;
+
+        let buf = buf.get_mut(..len).ok_or(Error::OutputSize {
+            provided: Ordering::Less,
+            expected: len,
+        })?;
+
+        let mut offset = 0;
+
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                // Valid characters are ASCII, so encode as a single byte
+                buf[offset] = sep as u8;
+                offset += 1;
             }
-        }
 
-        // Disallow leading zeroes
-        if value.starts_with('0') && value.len() > 1 {
-            return Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+            buf[offset..(offset + part.len())].copy_from_slice(part.as_bytes());
+            offset += part.len();
         }
 
-        value.parse().map_err(|_| {
-            // In theory a value overflow should be the only potential error here.
-            // When `ParseIntError::kind` is stable it might be good to double check:
-            // <https://github.com/rust-lang/rust/issues/22639>
-            Error::ParamValueInvalid(InvalidValue::InvalidFormat)
-        })
+        Ok(Value(str::from_utf8(buf).expect(INVARIANT_VIOLATED_MSG)))
     }
 
-    /// Does this value parse successfully as a decimal?
-    pub fn is_decimal(&self) -> bool {
-        self.decimal().is_ok()
-    }
-}
+    /// Construct a [`Value`] from the provided `str` by discarding any
+    /// characters which aren't allowed in a value, rather than rejecting the
+    /// input as [`Value::new`] does.
+    ///
+    /// The remaining characters are copied into `buf` and truncated to
+    /// [`Value::MAX_LENGTH`]. Returns [`Error::OutputSize`] if `buf` can't
+    /// hold them.
+    ///
+    /// This is intended for migrating legacy data where dropping a stray
+    /// character is preferable to discarding the whole value.
+    pub fn new_lossy<'b>(input: &str, buf: &'b mut [u8]) -> Result<Value<'b>> {
+        let valid_bytes = || {
+            input
+                .bytes()
+                .filter(|&b| is_byte_valid(b))
+                .take(Self::MAX_LENGTH)
+        };
 
-impl<'a> AsRef<str> for Value<'a> {
-    fn as_ref(&self) -> &str {
-        self.as_str()
+        let len = valid_bytes().count();
+        let buf = buf.get_mut(..len).ok_or(Error::OutputSize {
+            provided: Ordering::Less,
+            expected: len,
+        })?;
+
+        for (dst, b) in buf.iter_mut().zip(valid_bytes()) {
+            *dst = b;
+        }
+
+        Ok(Value(str::from_utf8(buf).expect(INVARIANT_VIOLATED_MSG)))
     }
-}
 
-impl<'a> TryFrom<&'a str> for Value<'a> {
-    type Error = Error;
+    /// Compact the provided buffer in place so that it contains only the
+    /// bytes which are allowed in a value, returning a [`Value`] over them.
+    ///
+    /// This is the in-place equivalent of [`Value::new_lossy`]: valid bytes
+    /// are shifted towards the front of `buf` in their original order,
+    /// invalid ones are dropped, and the result is truncated to
+    /// [`Value::MAX_LENGTH`]. The contents of `buf` beyond the returned
+    /// value are unspecified.
+    pub fn retain_valid<'b>(buf: &'b mut [u8]) -> Value<'b> {
+        let mut len = 0;
 
-    fn try_from(input: &'a str) -> Result<Self> {
-        Self::new(input)
+        for i in 0..buf.len() {
+            if len == Self::MAX_LENGTH {
+                break;
+            }
+
+            if is_byte_valid(buf[i]) {
+                buf[len] = buf[i];
+                len += 1;
+            }
+        }
+
+        Value(str::from_utf8(&buf[..len]).expect(INVARIANT_VIOLATED_MSG))
     }
-}
 
-impl<'a> TryFrom<Value<'a>> for Decimal {
-    type Error = Error;
+    /// Construct a [`Value`] from percent-encoded input (e.g. a PHC string
+    /// embedded in a URL), decoding it into the provided buffer.
+    ///
+    /// Each `%XX` sequence is replaced with the byte it encodes, and the
+    /// result is then validated as with [`Value::from_bytes`], so sequences
+    /// which decode to characters not allowed in a value are rejected.
+    /// A `%` which isn't followed by two hex digits is reported as
+    /// [`InvalidValue::InvalidFormat`], and [`Error::OutputSize`] is
+    /// returned if `buf` can't hold the decoded bytes.
+    ///
+    /// This is a compatibility helper: strict parsing with [`Value::new`]
+    /// never interprets percent encoding.
+    pub fn from_percent_encoded<'b>(input: &str, buf: &'b mut [u8]) -> Result<Value<'b>> {
+        let expected = input.len().saturating_sub(2 * input.matches('%').count());
+        let mut bytes = input.bytes();
+        let mut len = 0;
 
-    fn try_from(value: Value<'a>) -> Result<Decimal> {
-        Decimal::try_from(&value)
+        while let Some(b) = bytes.next() {
+            let b = if b == b'%' {
+                let mut hex_digit = || {
+                    bytes
+                        .next()
+                        .and_then(|d| char::from(d).to_digit(16))
+                        .ok_or(InvalidValue::InvalidFormat.param_error())
+                };
+
+                (hex_digit()? << 4 | hex_digit()?) as u8
+            } else {
+                b
+            };
+
+            *buf.get_mut(len).ok_or(Error::OutputSize {
+                provided: Ordering::Less,
+                expected,
+            })? = b;
+
+            len += 1;
+        }
+
+        Value::from_bytes(&buf[..len])
     }
-}
 
-impl<'a> TryFrom<&Value<'a>> for Decimal {
-    type Error = Error;
+    /// Write the canonical PHC encoding of the given [`Decimal`] into the
+    /// provided buffer, returning the result as a [`Value`].
+    ///
+    /// This is the inverse of [`Value::decimal`]. The result is always a
+    /// valid [`Value`] within [`Value::MAX_LENGTH`]. Returns
+    /// [`Error::OutputSize`] if `buf` is too short to hold it; a buffer of
+    /// [`MAX_DECIMAL_DIGITS`][`decimal::MAX_DECIMAL_DIGITS`] (i.e. 10) bytes
+    /// is always sufficient.
+    pub fn from_decimal<'b>(value: Decimal, buf: &'b mut [u8]) -> Result<Value<'b>> {
+        let mut digits = [0u8; decimal::MAX_DECIMAL_DIGITS];
+        Value(decimal::format_decimal(value, &mut digits))
+            .write_to(buf)
+            .map(Value)
+    }
+
+    /// Encode the given bytes as B64 into the provided buffer, returning
+    /// the result as a [`Value`].
+    ///
+    /// This is the inverse of [`Value::b64_decode`]. Returns an error if the
+    /// encoded result would exceed [`Value::MAX_LENGTH`], or
+    /// [`Error::OutputSize`] if `buf` is too small to hold it.
+    pub fn b64_encode<'b>(bytes: &[u8], buf: &'b mut [u8]) -> Result<Value<'b>> {
+        let len = Encoding::B64.encoded_len_for(bytes.len());
+        check_len(len, Self::MAX_LENGTH).map_err(InvalidValue::param_error)?;
+
+        let buf = buf.get_mut(..len).ok_or(Error::OutputSize {
+            provided: Ordering::Less,
+            expected: len,
+        })?;
 
-    fn try_from(value: &Value<'a>) -> Result<Decimal> {
-        value.decimal()
+        Value::new(Encoding::B64.encode(bytes, buf)?)
     }
-}
 
-impl<'a> fmt::Display for Value<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(self.as_str())
+    /// Attempt to decode a B64-encoded [`Value`], writing the decoded
+    /// result into the provided buffer, and returning a slice of the buffer
+    /// containing the decoded result on success.
+    ///
+    /// The empty value is valid B64, and decodes to an empty slice regardless
+    /// of the size of the provided buffer.
+    ///
+    /// Examples of "B64"-encoded parameters in practice are the `keyid` and
+    /// `data` parameters used by the [Argon2 Encoding][1] as described in the
+    /// PHC string format specification.
+    ///
+    /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
+    pub fn b64_decode<'b>(&self, buf: &'b mut [u8]) -> Result<&'b [u8]> {
+        self.decode_with(Encoding::B64, buf)
     }
-}
 
-/// Are all of the given bytes allowed in a [`Value`]?
-fn assert_valid_value(input: &str) -> Result<()> {
-    for c in input.chars() {
-        if !is_char_valid(c) {
-            return Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(c)));
-        }
+    /// Attempt to decode a B64-encoded [`Value`] into the provided buffer
+    /// like [`Value::b64_decode`], but return the number of bytes written
+    /// rather than a slice of the buffer.
+    ///
+    /// This is convenient for cursor-based buffer management, where the
+    /// caller advances a write position after each decoded value.
+    pub fn b64_decode_into(&self, buf: &mut [u8]) -> Result<usize> {
+        self.b64_decode(buf).map(<[u8]>::len)
     }
 
-    Ok(())
-}
+    /// Attempt to decode a B64-encoded [`Value`] into a possibly
+    /// uninitialized buffer, avoiding the need to zero it beforehand.
+    ///
+    /// On success, returns the number of bytes written, i.e. the length of
+    /// the prefix of `buf` which has been initialized with the decoded
+    /// result. Only that prefix is written, and nothing is read back from
+    /// `buf`.
+    ///
+    /// As this crate forbids `unsafe` code, it can't return the initialized
+    /// prefix as a `&[u8]` in the manner of [`Value::b64_decode`]. Callers
+    /// can soundly assume the first `n` elements of `buf` are initialized.
+    /// On error, a prefix of `buf` may have been partially written.
+    pub fn b64_decode_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> Result<usize> {
+        let len = self.b64_decoded_len()?;
+        let buf = buf.get_mut(..len).ok_or(B64Error::InvalidLength)?;
 
-/// Ensure the given ASCII character (i.e. byte) is allowed in a [`Value`].
-fn is_char_valid(c: char) -> bool {
-    matches!(c, 'A' ..= 'Z' | 'a'..='z' | '0'..='9' | '/' | '+' | '.' | '-')
-}
+        // Padding-free B64 can be decoded one 4-character block at a time
+        for (block, out) in self.as_bytes().chunks(4).zip(buf.chunks_mut(3)) {
+            let mut decoded = [0u8; 3];
+            let decoded = Encoding::B64.decode(block, &mut decoded)?;
 
-#[cfg(test)]
-mod tests {
-    use super::{Error, InvalidValue, Value};
+            for (dst, &byte) in out.iter_mut().zip(decoded) {
+                dst.write(byte);
+            }
+        }
 
-    // Invalid value examples
-    const INVALID_CHAR: &str = "x;y";
-    const INVALID_TOO_LONG: &str =
-        "01234567891123456789212345678931234567894123456785234567896234567";
-    const INVALID_CHAR_AND_TOO_LONG: &str =
-        "0!234567891123456789212345678931234567894123456785234567896234567";
+        Ok(len)
+    }
 
-    //
-    // Decimal parsing tests
-    //
+    /// Attempt to decode a B64-encoded [`Value`] into the provided buffer,
+    /// requiring the decoded output to fill it exactly.
+    ///
+    /// Returns [`Error::OutputSize`] if the decoded length differs from
+    /// `buf.len()` (in either direction), in which case `buf` is left
+    /// unmodified. This catches buffers sized incorrectly e.g. from
+    /// [`Value::b64_decoded_len`] rather than silently returning a shorter
+    /// slice as [`Value::b64_decode`] does.
+    pub fn b64_decode_exact(&self, buf: &mut [u8]) -> Result<()> {
+        let len = self.b64_decoded_len()?;
 
-    #[test]
-    fn decimal_value() {
-        let valid_decimals = &[("0", 0u32), ("1", 1u32), ("4294967295", u32::MAX)];
+        if buf.len() != len {
+            return Err(Error::OutputSize {
+                provided: buf.len().cmp(&len),
+                expected: len,
+            });
+        }
 
-        for &(s, i) in valid_decimals {
-            let value = Value::new(s).unwrap();
-            assert!(value.is_decimal());
-            assert_eq!(value.decimal().unwrap(), i)
+        self.b64_decode(buf)?;
+        Ok(())
+    }
+
+    /// Attempt to decode a B64-encoded [`Value`] into a fixed-size array.
+    ///
+    /// Returns [`Error::OutputSize`] if the decoded length is not exactly
+    /// `N` bytes, comparing `N` against the decoded length.
+    pub fn b64_decode_array<const N: usize>(&self) -> Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        let len = self.b64_decoded_len()?;
+
+        if len != N {
+            return Err(Error::OutputSize {
+                provided: N.cmp(&len),
+                expected: len,
+            });
         }
+
+        self.b64_decode(&mut bytes)?;
+        Ok(bytes)
     }
 
-    #[test]
-    fn reject_decimal_with_leading_zero() {
-        let value = Value::new("01").unwrap();
-        let err = u32::try_from(value).err().unwrap();
-        assert!(matches!(
-            err,
-            Error::ParamValueInvalid(InvalidValue::InvalidFormat)
-        ));
+    /// Attempt to decode a B64-encoded [`Value`] of at most 8 bytes as a
+    /// big-endian `u64`.
+    ///
+    /// Fewer than 8 bytes are treated as if padded with leading zeroes, so
+    /// e.g. an empty value decodes to `0`. Returns [`Error::OutputSize`] if
+    /// the decoded length exceeds 8 bytes, and [`Error::B64Encoding`] if the
+    /// value isn't valid B64.
+    pub fn b64_decode_u64_be(&self) -> Result<u64> {
+        let len = self.b64_decoded_len()?;
+
+        if len > 8 {
+            return Err(Error::OutputSize {
+                provided: Ordering::Greater,
+                expected: 8,
+            });
+        }
+
+        let mut bytes = [0u8; 8];
+        self.b64_decode(&mut bytes[(8 - len)..])?;
+        Ok(u64::from_be_bytes(bytes))
     }
 
-    #[test]
-    fn reject_overlong_decimal() {
-        let value = Value::new("4294967296").unwrap();
-        let err = u32::try_from(value).err().unwrap();
-        assert_eq!(err, Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+    /// Attempt to decode this [`Value`] using the given [`Encoding`], writing
+    /// the decoded result into the provided buffer, and returning a slice of
+    /// the buffer containing the decoded result on success.
+    ///
+    /// This allows decoding values which use e.g. the [`Encoding::Bcrypt`]
+    /// or [`Encoding::Crypt`] alphabets found in legacy MCF hashes, or any
+    /// other [`ValueEncoding`]. Characters outside of the chosen alphabet are
+    /// rejected.
+    pub fn decode_with<'b, E>(&self, encoding: E, buf: &'b mut [u8]) -> Result<&'b [u8]>
+    where
+        E: ValueEncoding,
+    {
+        let len = encoding.decoded_len(self.len())?;
+        let buf = buf.get_mut(..len).ok_or(B64Error::InvalidLength)?;
+        Ok(encoding.decode(self.as_bytes(), buf)?)
     }
 
-    #[test]
-    fn reject_negative() {
-        let value = Value::new("-1").unwrap();
-        let err = u32::try_from(value).err().unwrap();
-        assert!(matches!(
-            err,
-            Error::ParamValueInvalid(InvalidValue::InvalidChar(_))
-        ));
+    /// Decode this B64-encoded [`Value`] like [`Value::b64_decode`], but on
+    /// failure also return the byte offset at which the error occurred.
+    ///
+    /// See [`Value::decode_located`] for the details of the reported offset.
+    pub fn b64_decode_located<'b>(
+        &self,
+        buf: &'b mut [u8],
+    ) -> core::result::Result<&'b [u8], (Error, usize)> {
+        self.decode_located(Encoding::B64, buf)
     }
 
-    //
-    // String parsing tests
-    //
+    /// Decode this [`Value`] like [`Value::decode_with`], but on failure also
+    /// return the byte offset at which the error occurred.
+    ///
+    /// - For characters outside of the encoding's alphabet, the error is
+    ///   [`B64Error::InvalidEncoding`] and the offset is that of the first
+    ///   such character.
+    /// - For impossible lengths, the error is [`B64Error::InvalidLength`] and
+    ///   the offset is the length of the value.
+    /// - If `buf` is too small, the error is [`Error::OutputSize`] carrying
+    ///   the required length, and the offset is 0 as no input was consumed.
+    pub fn decode_located<'b>(
+        &self,
+        encoding: Encoding,
+        buf: &'b mut [u8],
+    ) -> core::result::Result<&'b [u8], (Error, usize)> {
+        let len = encoding
+            .decoded_len(self.len())
+            .map_err(|err| (err.into(), self.len()))?;
 
-    #[test]
-    fn string_value() {
-        let valid_examples = [
-            "",
-            "X",
-            "x",
-            "xXx",
-            "a+b.c-d",
-            "1/2",
-            "01234567891123456789212345678931",
-        ];
+        if buf.len() < len {
+            let err = Error::OutputSize {
+                provided: Ordering::Less,
+                expected: len,
+            };
 
-        for &example in &valid_examples {
-            let value = Value::new(example).unwrap();
-            assert_eq!(value.as_str(), example);
+            return Err((err, 0));
         }
+
+        if let Some(pos) = self
+            .as_bytes()
+            .iter()
+            .position(|&b| !encoding.is_alphabet_byte(b))
+        {
+            return Err((B64Error::InvalidEncoding.into(), pos));
+        }
+
+        // Every character is in the alphabet and the length is valid, so any
+        // remaining error can only be attributed to the final character.
+        let last = self.len().saturating_sub(1);
+        encoding
+            .decode(self.as_str(), &mut buf[..len])
+            .map_err(|err| (err.into(), last))
     }
 
-    #[test]
-    fn reject_invalid_char() {
-        let err = Value::new(INVALID_CHAR).err().unwrap();
-        assert!(matches!(
-            err,
-            Error::ParamValueInvalid(InvalidValue::InvalidChar(_))
-        ));
+    /// Compute the exact number of bytes [`Value::b64_decode`] will write
+    /// when decoding this [`Value`], based on its length.
+    ///
+    /// Returns an error if the length of this value is not a possible length
+    /// for padding-free B64 (i.e. `len % 4 == 1`). Note that this method
+    /// does not validate the contents of the value.
+    ///
+    /// The bcrypt and `crypt(3)` encodings are also padding-free, so this is
+    /// equally applicable to [`Value::decode_with`].
+    pub fn b64_decoded_len(&self) -> Result<usize> {
+        Ok(Encoding::B64.decoded_len(self.len())?)
+    }
+
+    /// Attempt to decode a hex-encoded [`Value`], writing the decoded
+    /// result into the provided buffer, and returning a slice of the buffer
+    /// containing the decoded result on success.
+    ///
+    /// Hex encoding is not part of the PHC string format specification, but
+    /// is supported for interoperability. See [`Encoding::Hex`].
+    pub fn hex_decode<'b>(&self, buf: &'b mut [u8]) -> Result<&'b [u8]> {
+        self.decode_with(Encoding::Hex, buf)
+    }
+
+    /// Decode this B64-encoded [`Value`] into a newly allocated byte vector.
+    ///
+    /// For values parsed with [`Value::new`], the decoded output is at most
+    /// 48 bytes, i.e. the number of bytes encoded by [`Value::MAX_LENGTH`]
+    /// B64 characters.
+    #[cfg(feature = "alloc")]
+    pub fn b64_decode_vec(&self) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; self.b64_decoded_len()?];
+        self.b64_decode(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Borrow this value as a `str`.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Consume this value, returning the underlying `str` with its original
+    /// lifetime.
+    pub fn into_str(self) -> &'a str {
+        self.0
+    }
+
+    /// Borrow this value as bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.as_str().as_bytes()
+    }
+
+    /// Iterate over the characters of this value.
+    ///
+    /// Every character yielded is ASCII and one of [`Value::VALID_CHARS`].
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        self.bytes().map(char::from)
+    }
+
+    /// Iterate over the bytes of this value.
+    ///
+    /// Every byte yielded is an ASCII character in [`Value::VALID_CHARS`].
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + 'a {
+        self.as_bytes().iter().map(|&b| {
+            debug_assert!(is_byte_valid(b), "{}", INVARIANT_VIOLATED_MSG);
+            b
+        })
+    }
+
+    /// Fold over the bytes of this value with a fallible closure, stopping
+    /// at the first error.
+    ///
+    /// This allows e.g. feeding a value into a checksum without copying it.
+    pub fn try_fold_bytes<B, E, F>(&self, init: B, mut f: F) -> core::result::Result<B, E>
+    where
+        F: FnMut(B, u8) -> core::result::Result<B, E>,
+    {
+        self.as_bytes().iter().try_fold(init, |acc, &b| f(acc, b))
+    }
+
+    /// Compute a stable hash of this value, e.g. for partitioning work
+    /// across shards by parameter value.
+    ///
+    /// This is the 64-bit FNV-1a hash of the value's bytes, so unlike the
+    /// [`Hash`] impl (whose output depends on the [`Hasher`] used) it is the
+    /// same across runs, platforms, and crate versions. It is **not** a
+    /// cryptographic hash, and isn't resistant to deliberate collisions.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write(self.as_bytes());
+        hasher.finish()
+    }
+
+    /// Get the length of this value in ASCII characters.
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Is this value empty?
+    pub fn is_empty(&self) -> bool {
+        self.as_str().is_empty()
+    }
+
+    /// Check that every character of this value satisfies the given
+    /// predicate, for algorithms which only allow a subset of
+    /// [`Value::VALID_CHARS`] in a particular parameter.
+    ///
+    /// Returns [`InvalidValue::InvalidChar`] for the first character which
+    /// doesn't.
+    pub fn validate_chars<F>(&self, pred: F) -> Result<()>
+    where
+        F: Fn(char) -> bool,
+    {
+        match self.chars().find(|&c| !pred(c)) {
+            Some(c) => Err(InvalidValue::InvalidChar(c).param_error()),
+            None => Ok(()),
+        }
+    }
+
+    /// Is this value ASCII?
+    ///
+    /// This is always `true`, as all of [`Value::VALID_CHARS`] are ASCII. It
+    /// exists to make that guarantee explicit for generic code, e.g. that
+    /// [`Value::len`] is both the number of characters and of bytes.
+    pub fn is_ascii(&self) -> bool {
+        let is_ascii = self.as_str().is_ascii();
+        debug_assert!(is_ascii, "{}", INVARIANT_VIOLATED_MSG);
+        is_ascii
+    }
+
+    /// Get the number of characters in this value.
+    ///
+    /// As values are always ASCII, this is equal to [`Value::len`].
+    pub fn char_count(&self) -> usize {
+        debug_assert!(self.is_ascii());
+        self.len()
+    }
+
+    /// Copy this value into an [`ArrayVec`][`arrayvec::ArrayVec`] with a
+    /// capacity of [`Value::MAX_LENGTH`].
+    ///
+    /// This can't fail for values parsed with [`Value::new`], but returns
+    /// [`Error::OutputSize`] for those parsed with a larger maximum length
+    /// using [`Value::new_with_max`].
+    #[cfg(feature = "arrayvec")]
+    pub fn to_arrayvec(&self) -> Result<arrayvec::ArrayVec<u8, { Value::MAX_LENGTH }>> {
+        self.as_bytes().try_into().map_err(|_| Error::OutputSize {
+            provided: Ordering::Less,
+            expected: self.len(),
+        })
+    }
+
+    /// Copy this value into the provided buffer, returning a `str` which
+    /// borrows from it.
+    ///
+    /// The buffer must be at least [`Value::len`] bytes, which is at most
+    /// [`Value::MAX_LENGTH`] for values parsed with [`Value::new`]. Returns
+    /// [`Error::OutputSize`] if it is too small.
+    pub fn write_to<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str> {
+        let buf = self.copy_into(buf)?;
+        Ok(str::from_utf8(buf).expect(INVARIANT_VIOLATED_MSG))
+    }
+
+    /// Copy this value into a [`Value::MAX_LENGTH`]-byte array padded with
+    /// zeroes, returning it along with the length of the value.
+    ///
+    /// This provides a fixed-size representation e.g. for storage or hashing.
+    /// The padding byte `0` isn't a valid value character, so it can't be
+    /// confused with the contents. Returns [`Error::OutputSize`] for values
+    /// parsed with a larger maximum length using [`Value::new_with_max`].
+    pub fn to_padded(&self) -> Result<([u8; Value::MAX_LENGTH], usize)> {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        let len = self.copy_into(&mut buf)?.len();
+        Ok((buf, len))
+    }
+
+    /// Copy the bytes of this value into the front of the provided buffer,
+    /// returning the portion of the buffer which was written.
+    fn copy_into<'b>(&self, buf: &'b mut [u8]) -> Result<&'b mut [u8]> {
+        let buf = buf.get_mut(..self.len()).ok_or(Error::OutputSize {
+            provided: Ordering::Less,
+            expected: self.len(),
+        })?;
+
+        buf.copy_from_slice(self.as_bytes());
+        Ok(buf)
+    }
+
+    /// Get a [`NormalizedValue`] for this value, which compares and hashes
+    /// decimals by their integer value.
+    pub fn normalized_key(&self) -> NormalizedValue<'a> {
+        NormalizedValue(*self)
+    }
+
+    /// Does this value match the given glob pattern?
+    ///
+    /// In the pattern, `*` matches any (possibly empty) run of characters,
+    /// `?` matches exactly one character, and every other character matches
+    /// only itself. There is no escape syntax. The pattern needn't be a
+    /// valid [`Value`], although characters outside of
+    /// [`Value::VALID_CHARS`] can only ever be matched by a wildcard.
+    ///
+    /// This is intended as a filtering convenience for tooling rather than
+    /// for parsing.
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        let (pattern, value) = (pattern.as_bytes(), self.as_bytes());
+        let (mut p, mut v) = (0, 0);
+
+        // Position of the last `*` in the pattern, and the value position it
+        // is currently assumed to match up to
+        let mut backtrack = None;
+
+        while v < value.len() {
+            match pattern.get(p) {
+                Some(b'*') => {
+                    backtrack = Some((p, v));
+                    p += 1;
+                    continue;
+                }
+                Some(&b) if b == b'?' || b == value[v] => {
+                    p += 1;
+                    v += 1;
+                    continue;
+                }
+                _ => (),
+            }
+
+            // Mismatch: let the last `*` consume one more character
+            match backtrack {
+                Some((star, start)) => {
+                    backtrack = Some((star, start + 1));
+                    p = star + 1;
+                    v = start + 1;
+                }
+                None => return false,
+            }
+        }
+
+        pattern[p..].iter().all(|&b| b == b'*')
+    }
+
+    /// Borrow the given byte range of this value as a [`Value`], or `None`
+    /// if it's out of bounds.
+    ///
+    /// As values are ASCII every byte index is a character boundary, and any
+    /// range of a valid [`Value`] is itself a valid [`Value`].
+    pub fn substr(&self, range: Range<usize>) -> Option<Value<'a>> {
+        self.0.get(range).map(Value)
+    }
+
+    /// Return the remainder of this value after removing the given prefix,
+    /// or `None` if it doesn't start with `prefix`.
+    ///
+    /// The remainder of a valid [`Value`] is itself a valid [`Value`], so
+    /// this requires no additional validation.
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Value<'a>> {
+        self.0.strip_prefix(prefix).map(Value)
+    }
+
+    /// Return the remainder of this value after removing the given suffix,
+    /// or `None` if it doesn't end with `suffix`.
+    pub fn strip_suffix(&self, suffix: &str) -> Option<Value<'a>> {
+        self.0.strip_suffix(suffix).map(Value)
+    }
+
+    /// Compare two values, treating decimals which denote the same integer
+    /// as equal even if either isn't canonically encoded (e.g. `042` and
+    /// `42`), and comparing all other values byte-for-byte.
+    ///
+    /// This is the equivalence used by [`NormalizedValue`]. Unlike the
+    /// [`PartialEq`] impl, it is intended for e.g. checking whether a
+    /// re-serialized value still denotes the same parameter as its source:
+    /// if the two are `value_eq` but not `==`, the source used a
+    /// non-canonical encoding.
+    pub fn value_eq(&self, other: &Value<'_>) -> bool {
+        self.normalized_key().key() == other.normalized_key().key()
+    }
+
+    /// Compare two values numerically, if both are decimals.
+    ///
+    /// Returns `None` unless both values parse with
+    /// [`Value::decimal_signed`]. Unlike the lexicographic [`Ord`] impl this
+    /// orders e.g. `"9"` before `"10"`.
+    pub fn cmp_numeric(&self, other: &Value<'_>) -> Option<Ordering> {
+        let a = self.decimal_signed().ok()?;
+        let b = other.decimal_signed().ok()?;
+        Some(a.cmp(&b))
+    }
+
+    /// Split this value on the given separator, returning an iterator over
+    /// the resulting segments as [`Value`]s borrowing from the same string.
+    ///
+    /// This is intended for algorithms which pack several sub-values into a
+    /// single parameter using e.g. `.` or `-` as internal separators. Every
+    /// segment of a valid [`Value`] is itself a valid [`Value`], so no
+    /// additional validation or allocation is required.
+    ///
+    /// # Panics
+    /// In debug builds, if `sep` is not one of [`Value::VALID_CHARS`]: no
+    /// other character can occur in a value.
+    pub fn split(&self, sep: char) -> impl Iterator<Item = Value<'a>> {
+        debug_assert!(is_char_valid(sep), "separator is not a valid value char");
+        self.0.split(sep).map(Value)
+    }
+
+    /// Get the number of leading bytes this value has in common with
+    /// another.
+    pub fn common_prefix_len(&self, other: &Value<'_>) -> usize {
+        self.as_bytes()
+            .iter()
+            .zip(other.as_bytes())
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    /// Compare this [`Value`] to another, ignoring ASCII case.
+    ///
+    /// This is intended for parameters an algorithm defines as
+    /// case-insensitive. The [`PartialEq`] impl for [`Value`] is always
+    /// byte-exact.
+    pub fn eq_ignore_ascii_case(&self, other: &Value<'_>) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+
+    /// Write an ASCII-lowercased copy of this [`Value`] into the provided
+    /// buffer, returning a [`Value`] which borrows from it.
+    ///
+    /// Only `A-Z` are affected; digits and `/+.-` are passed through as is.
+    pub fn to_ascii_lowercase_into<'b>(&self, buf: &'b mut [u8]) -> Result<Value<'b>> {
+        let buf = self.copy_into(buf)?;
+        buf.make_ascii_lowercase();
+        Ok(Value(str::from_utf8(buf).expect(INVARIANT_VIOLATED_MSG)))
+    }
+
+    /// Attempt to parse this [`Value`] as a PHC-encoded decimal (i.e. integer).
+    ///
+    /// Decimal values are integers which follow the rules given in the
+    /// ["Decimal Encoding" section of the PHC string format specification][1].
+    ///
+    /// The decimal encoding rules are as follows:
+    /// > For an integer value x, its decimal encoding consist in the following:
+    /// >
+    /// > - If x < 0, then its decimal encoding is the minus sign - followed by the decimal
+    /// >   encoding of -x.
+    /// > - If x = 0, then its decimal encoding is the single character 0.
+    /// > - If x > 0, then its decimal encoding is the smallest sequence of ASCII digits that
+    /// >   matches its value (i.e. there is no leading zero).
+    /// >
+    /// > Thus, a value is a valid decimal for an integer x if and only if all of the following hold true:
+    /// >
+    /// > - The first character is either a - sign, or an ASCII digit.
+    /// > - All characters other than the first are ASCII digits.
+    /// > - If the first character is - sign, then there is at least another character, and the
+    /// >   second character is not a 0.
+    /// > - If the string consists in more than one character, then the first one cannot be a 0.
+    ///
+    /// Note: this method does not support negative decimals despite them
+    /// being allowed per the spec above. If you need to parse a negative
+    /// number, use [`Value::decimal_signed`] instead.
+    ///
+    /// Values which overflow a [`Decimal`], such as any with more than
+    /// [`MAX_DECIMAL_DIGITS`][`decimal::MAX_DECIMAL_DIGITS`] digits, are
+    /// rejected with [`InvalidValue::TooLong`].
+    ///
+    /// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#decimal-encoding
+    pub fn decimal(&self) -> Result<Decimal> {
+        decimal::parse_decimal(self.as_bytes())
+    }
+
+    /// Attempt to parse this [`Value`] as a PHC-encoded decimal of the
+    /// given unsigned integer type `T`.
+    ///
+    /// This uses the same parser as [`Value::decimal`], so the same decimal
+    /// encoding rules apply and the same errors are returned, with values
+    /// which overflow `T` rejected with [`InvalidValue::TooLong`].
+    pub fn decimal_as<T: FromDecimal>(&self) -> Result<T> {
+        decimal::parse_digits(self.as_bytes(), 10)
+    }
+
+    /// Attempt to parse this [`Value`] as a PHC-encoded decimal, clamping
+    /// values which overflow [`Decimal`] to [`Decimal::MAX`].
+    ///
+    /// Values which aren't decimals (e.g. invalid characters or leading
+    /// zeroes) are still rejected as with [`Value::decimal`]; only numeric
+    /// overflow is tolerated.
+    pub fn decimal_saturating(&self) -> Result<Decimal> {
+        let digits = self.as_bytes();
+        decimal::assert_valid_digits(digits)?;
+
+        Ok(digits.iter().fold(0, |acc: Decimal, &b| {
+            acc.saturating_mul(10)
+                .saturating_add(Decimal::from(b - b'0'))
+        }))
+    }
+
+    /// Attempt to parse this [`Value`] as a decimal, tolerating a leading `+`
+    /// sign as emitted by some non-conformant producers.
+    ///
+    /// If present, the `+` is stripped and the remainder is parsed with the
+    /// same rules as [`Value::decimal`]. As with the `-` sign of a negative
+    /// decimal, the character following a `+` can't be `0`, so `+0` and
+    /// `+01` are both rejected. The strict [`Value::decimal`] should be
+    /// preferred wherever conformant input can be expected.
+    pub fn decimal_tolerant(&self) -> Result<Decimal> {
+        match self.as_bytes().split_first() {
+            Some((b'+', [b'0', ..])) => Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat)),
+            Some((b'+', digits)) => decimal::parse_decimal(digits),
+            _ => self.decimal(),
+        }
+    }
+
+    /// Attempt to parse this [`Value`] as a decimal, tolerating leading
+    /// zeroes, and report whether it was canonically encoded.
+    ///
+    /// Returns the parsed [`Decimal`] along with `true` if the value was a
+    /// canonical PHC decimal (i.e. [`Value::decimal`] would accept it), or
+    /// `false` if it had leading zeroes which were stripped. Values which
+    /// aren't digits at all are still rejected as with [`Value::decimal`].
+    pub fn decimal_lenient(&self) -> Result<(Decimal, bool)> {
+        let value = self.as_str();
+
+        if value.is_empty() {
+            return Err(Error::ParamValueInvalid(InvalidValue::Malformed));
+        }
+
+        let digits = match value.trim_start_matches('0') {
+            "" => "0",
+            digits => digits,
+        };
+
+        let n = decimal::parse_decimal(digits.as_bytes())?;
+        Ok((n, digits.len() == value.len()))
+    }
+
+    /// Parse this [`Value`] as a PHC-encoded decimal, additionally requiring
+    /// it to lie within the given inclusive range, e.g. an algorithm's
+    /// permitted cost parameters.
+    ///
+    /// Returns [`InvalidValue::OutOfRange`] if it doesn't, or the same errors
+    /// as [`Value::decimal`] if it isn't a valid decimal.
+    pub fn decimal_in_range(&self, range: RangeInclusive<Decimal>) -> Result<Decimal> {
+        let n = self.decimal()?;
+
+        if !range.contains(&n) {
+            return Err(InvalidValue::OutOfRange.param_error());
+        }
+
+        Ok(n)
+    }
+
+    /// Parse this [`Value`] as a PHC-encoded decimal and multiply it by
+    /// `rhs`, e.g. to convert a memory cost in blocks into bytes.
+    ///
+    /// Returns [`InvalidValue::TooLong`] if the product overflows
+    /// [`Decimal`], or the same errors as [`Value::decimal`] otherwise.
+    pub fn decimal_checked_mul(&self, rhs: Decimal) -> Result<Decimal> {
+        self.decimal()?
+            .checked_mul(rhs)
+            .ok_or(Error::ParamValueInvalid(InvalidValue::TooLong))
+    }
+
+    /// Parse this [`Value`] as a PHC-encoded decimal, returning `default` if
+    /// it is not a valid [`Decimal`].
+    ///
+    /// Use [`Value::decimal`] where malformed or overflowing values must be
+    /// reported, or [`Value::decimal_or_else`] to handle them differently.
+    pub fn decimal_or(&self, default: Decimal) -> Decimal {
+        self.decimal().unwrap_or(default)
+    }
+
+    /// Parse this [`Value`] as a PHC-encoded decimal, computing a fallback
+    /// from the error with `f` if it is not a valid [`Decimal`].
+    ///
+    /// The error passed to `f` distinguishes malformed values (e.g.
+    /// [`InvalidValue::InvalidChar`]) from overflowing ones.
+    pub fn decimal_or_else<F>(&self, f: F) -> Decimal
+    where
+        F: FnOnce(Error) -> Decimal,
+    {
+        self.decimal().unwrap_or_else(f)
+    }
+
+    /// Attempt to parse this [`Value`] as an unsigned integer in the given
+    /// radix, e.g. 16 for hexadecimal or 8 for octal.
+    ///
+    /// The same rules as [`Value::decimal`] apply, using the radix's digit
+    /// set (`0-9` followed by `a-z`/`A-Z`). In particular leading zeroes are
+    /// rejected. For a radix of 10 this is identical to [`Value::decimal`].
+    ///
+    /// # Panics
+    /// If `radix` is not in the range `2..=36`.
+    pub fn decimal_radix(&self, radix: u32) -> Result<Decimal> {
+        decimal::parse_radix(self.as_bytes(), radix)
+    }
+
+    /// Guess how this value should be interpreted, e.g. for rendering it in
+    /// generic tooling.
+    ///
+    /// The rules are applied in order:
+    ///
+    /// 1. Values which follow the PHC decimal encoding (see
+    ///    [`Value::new_decimal`]) are [`ValueKind::Decimal`], regardless of
+    ///    magnitude. Decimals are never classified as B64.
+    /// 2. Nonempty values which decode as [`Encoding::B64`] are
+    ///    [`ValueKind::MaybeB64`].
+    /// 3. Everything else, including the empty value, is [`ValueKind::Text`].
+    pub fn classify(&self) -> ValueKind {
+        if decimal::assert_valid_signed(self.as_bytes()).is_ok() {
+            ValueKind::Decimal
+        } else if !self.is_empty() && self.is_b64() {
+            ValueKind::MaybeB64
+        } else {
+            ValueKind::Text
+        }
+    }
+
+    /// Does this value parse successfully as a decimal?
+    pub fn is_decimal(&self) -> bool {
+        self.decimal().is_ok()
+    }
+
+    /// Is this value valid B64, i.e. does it decode successfully with
+    /// [`Value::b64_decode`]?
+    ///
+    /// This checks the characters, the length, and that the unused low bits
+    /// of the final character are zero (e.g. `AB` is rejected in favour of
+    /// `AA`) without decoding, so if it returns `true`, decoding into a
+    /// buffer of [`Value::b64_decoded_len`] bytes can't fail. The empty value
+    /// is valid B64.
+    pub fn is_b64(&self) -> bool {
+        Encoding::B64.is_valid(self.as_str())
+    }
+
+    /// Does this value mix characters which are easily confused with each
+    /// other when displayed or transcribed by hand?
+    ///
+    /// The ambiguous groups are:
+    ///
+    /// - `0` (zero), `O` (uppercase o), and `o` (lowercase o)
+    /// - `1` (one), `I` (uppercase i), and `l` (lowercase L)
+    ///
+    /// A value is ambiguous when it contains two or more distinct characters
+    /// from the same group, e.g. `O0` or `l1`. Repeating a single character
+    /// from a group (e.g. `1000`) is not ambiguous.
+    ///
+    /// This is a heuristic intended for tooling which flags likely typos in
+    /// manually entered parameters. Since values are restricted to
+    /// [`Value::VALID_CHARS`], Unicode confusables can't occur.
+    pub fn has_ambiguous_chars(&self) -> bool {
+        const AMBIGUOUS_GROUPS: &[&[u8]] = &[b"0Oo", b"1Il"];
+
+        AMBIGUOUS_GROUPS.iter().any(|group| {
+            group
+                .iter()
+                .filter(|&&c| self.as_bytes().contains(&c))
+                .count()
+                > 1
+        })
+    }
+
+    /// Attempt to parse this [`Value`] as a PHC-encoded signed decimal.
+    ///
+    /// Unlike [`Value::decimal`], this method implements the full set of
+    /// decimal encoding rules from the PHC string format specification,
+    /// including negative values, i.e. it accepts an optional leading `-`
+    /// sign which must be followed by a nonzero digit. This means `-0`,
+    /// `-01`, and a lone `-` are all rejected.
+    ///
+    /// Returns [`InvalidValue::TooLong`] if the value overflows an `i64`.
+    pub fn decimal_signed(&self) -> Result<i64> {
+        let value = self.as_str();
+        decimal::assert_valid_signed(value.as_bytes())?;
+
+        value
+            .parse()
+            .map_err(|_| Error::ParamValueInvalid(InvalidValue::TooLong))
+    }
+}
+
+impl Default for Value<'_> {
+    fn default() -> Self {
+        Value::EMPTY
+    }
+}
+
+impl<'a> AsRef<str> for Value<'a> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Value<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self> {
+        Self::new(input)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Value<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a [u8]) -> Result<Self> {
+        Self::from_bytes(input)
+    }
+}
+
+impl<'a, const N: usize> TryFrom<&'a [u8; N]> for Value<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a [u8; N]) -> Result<Self> {
+        Self::from_bytes(input)
+    }
+}
+
+impl<'a> PartialEq<str> for Value<'a> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for Value<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<'a> PartialEq<Value<'a>> for str {
+    fn eq(&self, other: &Value<'a>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<'a> PartialEq<Value<'a>> for &str {
+    fn eq(&self, other: &Value<'a>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+/// Values are equal to a [`Decimal`] only if they parse successfully with
+/// [`Value::decimal`] as exactly that decimal. In particular
+/// non-canonical encodings such as `"032"` are never equal to any decimal.
+impl<'a> PartialEq<Decimal> for Value<'a> {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.decimal().ok() == Some(*other)
+    }
+}
+
+impl<'a> PartialEq<Value<'a>> for Decimal {
+    fn eq(&self, other: &Value<'a>) -> bool {
+        other == self
+    }
+}
+
+/// Implement `TryFrom<Value>` for the unsigned integer widths in terms of
+/// [`Value::decimal_as`], so every width (including [`Decimal`]) is parsed
+/// by the same checked-digit parser and reports overflow as
+/// [`InvalidValue::TooLong`].
+macro_rules! impl_try_from_value {
+    ($($int:ty),+) => {
+        $(
+            impl<'a> TryFrom<Value<'a>> for $int {
+                type Error = Error;
+
+                fn try_from(value: Value<'a>) -> Result<$int> {
+                    value.decimal_as()
+                }
+            }
+
+            impl<'a> TryFrom<&Value<'a>> for $int {
+                type Error = Error;
+
+                fn try_from(value: &Value<'a>) -> Result<$int> {
+                    value.decimal_as()
+                }
+            }
+        )+
+    };
+}
+
+impl_try_from_value!(u8, u16, u32, u64, usize);
+
+impl<'a> ConstantTimeEq for Value<'a> {
+    /// Compare two values in constant time.
+    ///
+    /// Both values are compared as if zero-padded to at least
+    /// [`Value::MAX_LENGTH`] bytes, so the comparison doesn't reveal the
+    /// position of the first difference or the length of the values.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (a, b) = (self.as_bytes(), other.as_bytes());
+        let len = Self::MAX_LENGTH.max(a.len()).max(b.len());
+        let mut result = a.len().ct_eq(&b.len());
+
+        for i in 0..len {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            result &= x.ct_eq(&y);
+        }
+
+        result
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Value<'a> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> serde::Deserialize<'de> for Value<'a> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = <&'de str>::deserialize(deserializer)?;
+        Self::new(s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<'a> fmt::Display for Value<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Heuristic classification of a [`Value`], as returned by
+/// [`Value::classify`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValueKind {
+    /// PHC-encoded decimal.
+    Decimal,
+
+    /// Possibly B64-encoded binary data.
+    MaybeB64,
+
+    /// Any other text.
+    Text,
+}
+
+/// [`Value`] wrapper whose [`Eq`] and [`Hash`] impls compare decimals by
+/// their integer value rather than their string encoding.
+///
+/// Values consisting of ASCII digits, optionally preceded by a `-` sign,
+/// are keyed by the integer they encode, and all other values by their
+/// bytes. This makes it usable as a consistent deduplication key for
+/// parameter maps.
+///
+/// Unlike [`Value::decimal_signed`], the decimal encoding rules aren't
+/// enforced, so non-canonical encodings such as `042` and `-0` compare
+/// equal to `42` and `0` respectively. Decimals which overflow an `i64`
+/// are compared by their bytes. The byte-exact comparisons of [`Value`] are
+/// unchanged.
+#[derive(Copy, Clone, Debug)]
+pub struct NormalizedValue<'a>(Value<'a>);
+
+impl<'a> NormalizedValue<'a> {
+    /// Borrow the underlying [`Value`].
+    pub fn as_value(&self) -> Value<'a> {
+        self.0
+    }
+
+    /// Compute the key used for comparisons and hashing.
+    fn key(&self) -> NormalizedKey<'a> {
+        match decimal::parse_signed_lenient(self.0.as_bytes()) {
+            Some(n) => NormalizedKey::Decimal(n),
+            None => NormalizedKey::Str(self.0.as_str()),
+        }
+    }
+}
+
+impl<'a> From<Value<'a>> for NormalizedValue<'a> {
+    fn from(value: Value<'a>) -> Self {
+        Self(value)
+    }
+}
+
+impl<'a> PartialEq for NormalizedValue<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<'a> Eq for NormalizedValue<'a> {}
+
+impl<'a> Hash for NormalizedValue<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+/// Comparison key for a [`NormalizedValue`].
+#[derive(Eq, Hash, PartialEq)]
+enum NormalizedKey<'a> {
+    Decimal(i64),
+    Str(&'a str),
+}
+
+/// Owned stack-allocated equivalent of [`Value`].
+///
+/// Unlike [`Value`], this type implements [`FromStr`][`str::FromStr`], which
+/// allows it to be used with `str::parse` and in generic code bounded on
+/// [`FromStr`][`str::FromStr`]. Use [`ValueBuf::as_value`] to access the
+/// [`Value`] accessors.
+///
+/// As it doesn't borrow from the string it was parsed from, it can also be
+/// stored independently of it, e.g. as a map key. It implements
+/// [`Borrow<str>`] so such maps can be queried with a `&str`.
+///
+/// It doesn't require `alloc`, and is [`Copy`] as its contents are stored
+/// inline in a [`Value::MAX_LENGTH`]-byte array.
+#[derive(Clone, Copy, Eq)]
+pub struct ValueBuf {
+    /// ASCII-encoded characters which comprise the value.
+    bytes: [u8; Value::MAX_LENGTH],
+
+    /// Length of the string in ASCII characters (i.e. bytes).
+    length: u8,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl ValueBuf {
+    /// Parse a [`ValueBuf`] from the provided `str`, validating it according
+    /// to the same rules as [`Value::new`].
+    pub fn new(s: &str) -> Result<Self> {
+        // Assert `s` parses successfully as a `Value`
+        Value::new(s)?;
+
+        let mut bytes = [0u8; Value::MAX_LENGTH];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+
+        Ok(Self {
+            bytes,
+            length: s.len() as u8, // `Value::new` check prevents overflow
+        })
+    }
+
+    /// Encode the given bytes as B64 into a new [`ValueBuf`], as with
+    /// [`Value::b64_encode`].
+    pub fn b64_encode(bytes: &[u8]) -> Result<Self> {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        let length = Value::b64_encode(bytes, &mut buf)?.len() as u8;
+        Ok(Self { bytes: buf, length })
+    }
+
+    /// Borrow the contents of a [`ValueBuf`] as a [`Value`].
+    pub fn as_value(&self) -> Value<'_> {
+        Value::new(self.as_str()).expect(INVARIANT_VIOLATED_MSG)
+    }
+
+    /// Borrow the contents of a [`ValueBuf`] as a `str`.
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.bytes[..(self.length as usize)]).expect(INVARIANT_VIOLATED_MSG)
+    }
+
+    /// Borrow the contents of a [`ValueBuf`] as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+
+    /// Get the length of this value in ASCII characters.
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+}
+
+impl AsRef<str> for ValueBuf {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for ValueBuf {
+    fn eq(&self, other: &Self) -> bool {
+        // Ensure comparisons always honor the initialized portion of the buffer
+        self.as_ref().eq(other.as_ref())
+    }
+}
+
+impl Borrow<str> for ValueBuf {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Hash for ValueBuf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must be consistent with `Borrow<str>`
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialOrd for ValueBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueBuf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl str::FromStr for ValueBuf {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl From<Decimal> for ValueBuf {
+    fn from(decimal: Decimal) -> ValueBuf {
+        let mut bytes = [0u8; Value::MAX_LENGTH];
+        let length = decimal::format_decimal(decimal, &mut bytes).len() as u8;
+        Self { bytes, length }
+    }
+}
+
+impl TryFrom<&str> for ValueBuf {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for ValueBuf {
+    type Error = Error;
+
+    /// Copy a [`Value`] into a [`ValueBuf`].
+    ///
+    /// Fails if the value exceeds [`Value::MAX_LENGTH`], which is possible
+    /// for values parsed with [`Value::new_with_max`].
+    fn try_from(value: Value<'a>) -> Result<Self> {
+        Self::new(value.as_str())
+    }
+}
+
+impl<'a> From<&'a ValueBuf> for Value<'a> {
+    fn from(value_buf: &'a ValueBuf) -> Value<'a> {
+        value_buf.as_value()
+    }
+}
+
+impl fmt::Display for ValueBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_value(), f)
+    }
+}
+
+impl fmt::Debug for ValueBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ValueBuf({:?})", self.as_str())
+    }
+}
+
+/// Owned [`Value`] for secret parameters which zeroizes its contents on drop.
+///
+/// This is intended for values such as the `keyid` and `data` parameters of
+/// the [Argon2 Encoding][1], which may contain secret material that
+/// shouldn't linger in memory. It's validated with the same rules as
+/// [`Value::new`].
+///
+/// To avoid accidentally logging its contents, [`SecretValue`] doesn't
+/// implement [`Display`][`fmt::Display`], and its [`Debug`][`fmt::Debug`]
+/// output is redacted. Use [`SecretValue::as_value`] to access the contents,
+/// and the [`ConstantTimeEq`] impl to compare them.
+///
+/// [1]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#argon2-encoding
+#[cfg(feature = "zeroize")]
+#[derive(Clone)]
+pub struct SecretValue(ValueBuf);
+
+#[cfg(feature = "zeroize")]
+impl SecretValue {
+    /// Parse a [`SecretValue`] from the provided `str`, validating it
+    /// according to the same rules as [`Value::new`].
+    pub fn new(s: &str) -> Result<Self> {
+        ValueBuf::new(s).map(Self)
+    }
+
+    /// Borrow this secret as a [`Value`].
+    pub fn as_value(&self) -> Value<'_> {
+        self.0.as_value()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a> TryFrom<Value<'a>> for SecretValue {
+    type Error = Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self> {
+        Self::new(value.as_str())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ConstantTimeEq for SecretValue {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.as_value().ct_eq(&other.as_value())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretValue {
+    fn zeroize(&mut self) {
+        self.0.bytes.zeroize();
+        self.0.length.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretValue {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretValue {}
+
+#[cfg(feature = "zeroize")]
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretValue(...)")
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ValueBuf {
+    /// Generate an arbitrary value consisting of [`Value::VALID_CHARS`] and
+    /// no longer than [`Value::MAX_LENGTH`].
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=Value::MAX_LENGTH)?;
+        let mut bytes = [0u8; Value::MAX_LENGTH];
+
+        for byte in &mut bytes[..len] {
+            *byte = *u.choose(Value::VALID_CHARS.as_bytes())?;
+        }
+
+        let s = str::from_utf8(&bytes[..len]).expect(INVARIANT_VIOLATED_MSG);
+        Ok(Self::new(s).expect(INVARIANT_VIOLATED_MSG))
+    }
+}
+
+/// Builder for a [`Value`] composed of several parts, written into a
+/// provided buffer.
+///
+/// Each part is validated as it's pushed: strings must be valid values,
+/// decimals are written in the canonical PHC encoding, separators must be
+/// one of [`Value::VALID_CHARS`], and the total length can't exceed
+/// [`Value::MAX_LENGTH`] (or the length of the buffer, which is reported as
+/// [`Error::OutputSize`]). As with [`ParamsBuilder`][`crate::ParamsBuilder`],
+/// errors are deferred until [`ValueWriter::finish`] so calls can be
+/// chained, and any parts pushed after an error are ignored.
+///
+/// ```
+/// use password_hash::ValueWriter;
+///
+/// let mut buf = [0u8; 16];
+/// let value = ValueWriter::new(&mut buf)
+///     .push_str("v")
+///     .push_decimal(19)
+///     .push_sep('.')
+///     .push_str("AQID")
+///     .finish()
+///     .unwrap();
+///
+/// assert_eq!(value, "v19.AQID");
+/// ```
+#[derive(Debug)]
+pub struct ValueWriter<'b> {
+    /// Buffer the value is written into.
+    buf: &'b mut [u8],
+
+    /// Number of bytes written to `buf` so far.
+    len: usize,
+
+    /// First error encountered while pushing parts, if any.
+    error: Option<Error>,
+}
+
+impl<'b> ValueWriter<'b> {
+    /// Create a new [`ValueWriter`] which writes into the given buffer.
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            error: None,
+        }
+    }
+
+    /// Append a string, which must be a valid [`Value`].
+    pub fn push_str(self, s: &str) -> Self {
+        match Value::new(s) {
+            Ok(value) => self.push_bytes(value.as_bytes()),
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Append a decimal in the canonical PHC encoding.
+    pub fn push_decimal(self, n: Decimal) -> Self {
+        let mut buf = [0u8; decimal::MAX_DECIMAL_DIGITS];
+        self.push_bytes(decimal::format_decimal(n, &mut buf).as_bytes())
+    }
+
+    /// Append a separator character, which must be one of
+    /// [`Value::VALID_CHARS`].
+    pub fn push_sep(self, sep: char) -> Self {
+        if is_char_valid(sep) {
+            self.push_bytes(&[sep as u8])
+        } else {
+            self.fail(InvalidValue::InvalidChar(sep).param_error())
+        }
+    }
+
+    /// Finish writing, returning the resulting [`Value`], or the first error
+    /// encountered while pushing parts.
+    pub fn finish(self) -> Result<Value<'b>> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let buf: &'b [u8] = self.buf;
+        Ok(Value(
+            str::from_utf8(&buf[..self.len]).expect(INVARIANT_VIOLATED_MSG),
+        ))
+    }
+
+    /// Append the given (already validated) bytes.
+    fn push_bytes(mut self, bytes: &[u8]) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+
+        let len = self.len + bytes.len();
+
+        if let Err(err) = check_len(len, Value::MAX_LENGTH) {
+            return self.fail(err.param_error());
+        }
+
+        match self.buf.get_mut(self.len..len) {
+            Some(dst) => dst.copy_from_slice(bytes),
+            None => {
+                return self.fail(Error::OutputSize {
+                    provided: Ordering::Less,
+                    expected: len,
+                })
+            }
+        }
+
+        self.len = len;
+        self
+    }
+
+    /// Record the given error unless one has already occurred.
+    fn fail(mut self, err: Error) -> Self {
+        self.error.get_or_insert(err);
+        self
+    }
+}
+
+/// Pool of interned [`Value`]s, for deduplicating parameter values which
+/// occur repeatedly, e.g. when verifying many hashes with the same params.
+///
+/// Each distinct string is validated and allocated once, and is then
+/// returned for every subsequent request, so interned values with the same
+/// contents share the same address and can be compared with
+/// [`core::ptr::eq`] on [`Value::as_str`].
+///
+/// The interner owns the interned strings, and the [`Value`]s it hands out
+/// borrow from it, so all of the memory is freed when it's dropped.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct ValueInterner {
+    values: BTreeSet<Box<str>>,
+}
+
+#[cfg(feature = "alloc")]
+impl ValueInterner {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the given string as a [`Value`] and return the interned copy
+    /// of it, allocating one if it hasn't been seen before.
+    pub fn intern(&mut self, input: &str) -> Result<Value<'_>> {
+        if !self.values.contains(input) {
+            Value::new(input)?;
+            self.values.insert(input.into());
+        }
+
+        Ok(self.get(input).expect(INVARIANT_VIOLATED_MSG))
+    }
+
+    /// Get the interned copy of the given string, if it has been interned.
+    ///
+    /// Unlike [`ValueInterner::intern`] this only borrows the interner, so
+    /// several interned values can be held at once.
+    pub fn get(&self, input: &str) -> Option<Value<'_>> {
+        self.values.get(input).map(|interned| Value(interned))
+    }
+
+    /// Number of distinct values which have been interned.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Has nothing been interned yet?
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// 64-bit FNV-1a [`Hasher`], as used by [`Value::stable_hash`].
+struct StableHasher(u64);
+
+impl StableHasher {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Create a new hasher in its initial state.
+    const fn new() -> Self {
+        Self(Self::FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(Self::FNV_PRIME);
+        }
+    }
+}
+
+/// Ensure the given length (in ASCII characters, i.e. bytes) doesn't exceed
+/// `max`.
+///
+/// This is shared by the parsers for all PHC string fields and is called
+/// before any characters are inspected, so the work done on overlong
+/// untrusted input is bounded by `max`.
+pub(crate) const fn check_len(len: usize, max: usize) -> core::result::Result<(), InvalidValue> {
+    if len > max {
+        return Err(InvalidValue::too_long(max, len));
+    }
+
+    Ok(())
+}
+
+/// Are all of the given bytes allowed in a [`Value`]?
+///
+/// On failure, returns the byte offset of the first invalid character.
+fn assert_valid_value(input: &str) -> core::result::Result<(), (Error, usize)> {
+    // All valid characters are ASCII, so scan bytes rather than decoding
+    // UTF-8. Any non-ASCII byte is invalid, and as everything before it is
+    // ASCII it starts a character which can be reported in the error.
+    match input.bytes().position(|b| !is_byte_valid(b)) {
+        Some(i) => {
+            let c = input[i..].chars().next().expect(INVARIANT_VIOLATED_MSG);
+            Err((Error::ParamValueInvalid(InvalidValue::InvalidChar(c)), i))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Ensure the given ASCII character (i.e. byte) is allowed in a [`Value`].
+const fn is_char_valid(c: char) -> bool {
+    matches!(c, 'A' ..= 'Z' | 'a'..='z' | '0'..='9' | '/' | '+' | '.' | '-')
+}
+
+/// Is the given byte allowed in a [`Value`]?
+///
+/// Equivalent to [`is_char_valid`], but uses a lookup table.
+const fn is_byte_valid(b: u8) -> bool {
+    VALID_BYTES[b as usize]
+}
+
+/// Lookup table of the bytes allowed in a [`Value`], indexed by byte.
+const VALID_BYTES: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut i = 0;
+
+    while i < table.len() {
+        table[i] = is_char_valid(i as u8 as char);
+        i += 1;
+    }
+
+    table
+};
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use super::ValueInterner;
+    use super::{
+        B64Error, Encoding, Error, InvalidValue, NormalizedValue, StableHasher, Value, ValueBuf,
+        ValueKind, ValueWriter,
+    };
+    use crate::decimal::{self, Decimal};
+    use core::cmp::Ordering;
+    use core::hash::{Hash, Hasher};
+
+    // Invalid value examples
+    const INVALID_CHAR: &str = "x;y";
+    const INVALID_TOO_LONG: &str =
+        "01234567891123456789212345678931234567894123456785234567896234567";
+    const INVALID_CHAR_AND_TOO_LONG: &str =
+        "0!234567891123456789212345678931234567894123456785234567896234567";
+
+    //
+    // Decimal parsing tests
+    //
+
+    #[test]
+    fn decimal_value() {
+        let valid_decimals = &[("0", 0u32), ("1", 1u32), ("4294967295", u32::MAX)];
+
+        for &(s, i) in valid_decimals {
+            let value = Value::new(s).unwrap();
+            assert!(value.is_decimal());
+            assert_eq!(value.decimal().unwrap(), i)
+        }
+    }
+
+    #[test]
+    fn reject_decimal_with_leading_zero() {
+        let value = Value::new("01").unwrap();
+        let err = u32::try_from(value).err().unwrap();
+        assert!(matches!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn reject_overlong_decimal() {
+        for &s in &["4294967296", "9999999999", "10000000000"] {
+            let value = Value::new(s).unwrap();
+            let err = u32::try_from(value).err().unwrap();
+            assert_eq!(err, Error::ParamValueInvalid(InvalidValue::TooLong));
+        }
+    }
+
+    #[test]
+    fn reject_negative() {
+        let value = Value::new("-1").unwrap();
+        let err = u32::try_from(value).err().unwrap();
+        assert!(matches!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(_))
+        ));
+    }
+
+    #[test]
+    fn decimal_as_value() {
+        fn assert_boundaries<T>(max: T, max_str: &str, overflow: &str)
+        where
+            T: crate::decimal::FromDecimal + core::fmt::Debug + PartialEq + From<u8>,
+        {
+            assert_eq!(Value::new(max_str).unwrap().decimal_as::<T>(), Ok(max));
+
+            let err = Value::new(overflow)
+                .unwrap()
+                .decimal_as::<T>()
+                .err()
+                .unwrap();
+            assert_eq!(err, Error::ParamValueInvalid(InvalidValue::TooLong));
+
+            assert_eq!(
+                Value::new("0").unwrap().decimal_as::<T>().unwrap(),
+                T::from(0)
+            );
+            assert_eq!(
+                Value::new("1").unwrap().decimal_as::<T>().unwrap(),
+                T::from(1)
+            );
+
+            let err = Value::new("01").unwrap().decimal_as::<T>().err().unwrap();
+            assert_eq!(err, Error::ParamValueInvalid(InvalidValue::InvalidFormat));
+
+            let err = Value::new("1x").unwrap().decimal_as::<T>().err().unwrap();
+            assert_eq!(
+                err,
+                Error::ParamValueInvalid(InvalidValue::InvalidChar('x'))
+            );
+        }
+
+        assert_boundaries(u8::MAX, "255", "256");
+        assert_boundaries(u16::MAX, "65535", "65536");
+        assert_boundaries(u32::MAX, "4294967295", "4294967296");
+        assert_boundaries(u64::MAX, "18446744073709551615", "18446744073709551616");
+        assert_boundaries(
+            u128::MAX,
+            "340282366920938463463374607431768211455",
+            "340282366920938463463374607431768211456",
+        );
+
+        // `decimal_as::<Decimal>` is identical to `decimal`
+        for &s in &["0", "42", "01", "-1", "4294967296", "x", ""] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.decimal_as::<Decimal>(), value.decimal(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn decimal_saturating() {
+        for &(s, i) in &[
+            ("0", 0),
+            ("4294967295", u32::MAX),
+            ("4294967296", u32::MAX),
+            ("99999999999", u32::MAX),
+        ] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.decimal_saturating().unwrap(), i);
+        }
+
+        let err = Value::new("099999999999").unwrap().decimal_saturating();
+        assert_eq!(
+            err,
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat))
+        );
+
+        let err = Value::new("9x").unwrap().decimal_saturating();
+        assert_eq!(
+            err,
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidChar('x')))
+        );
+    }
+
+    #[test]
+    fn matches_glob() {
+        let value = Value::new("v1.AQID").unwrap();
+
+        for &pattern in &[
+            "v1.AQID", "*", "v1.*", "*.AQID", "v?.A*D", "*A*I*", "**", "v1.AQID*",
+        ] {
+            assert!(value.matches_glob(pattern), "{}", pattern);
+        }
+
+        for &pattern in &["", "v1", "v2.*", "*.AQ", "v?.AQID?", "?", "*x*", "v1;*"] {
+            assert!(!value.matches_glob(pattern), "{}", pattern);
+        }
+
+        let empty = Value::new("").unwrap();
+        assert!(empty.matches_glob(""));
+        assert!(empty.matches_glob("*"));
+        assert!(!empty.matches_glob("?"));
+    }
+
+    #[test]
+    fn substr() {
+        let value = Value::new("v1.AQID").unwrap();
+        assert_eq!(value.substr(0..2).unwrap(), "v1");
+        assert_eq!(value.substr(3..7).unwrap(), "AQID");
+        assert_eq!(value.substr(7..7).unwrap(), "");
+        assert_eq!(value.substr(0..8), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = value.substr(3..2);
+        assert_eq!(reversed, None);
+    }
+
+    #[test]
+    fn strip_prefix_and_suffix() {
+        let value = Value::new("v1.AQID").unwrap();
+        assert_eq!(value.strip_prefix("v1.").unwrap(), "AQID");
+        assert_eq!(value.strip_prefix("v2."), None);
+        assert_eq!(value.strip_prefix(""), Some(value));
+        assert_eq!(value.strip_suffix(".AQID").unwrap(), "v1");
+        assert_eq!(value.strip_suffix("x"), None);
+        assert_eq!(value.strip_prefix("v1.AQID").unwrap(), "");
+    }
+
+    #[test]
+    fn split() {
+        let value = Value::new("1.22.333").unwrap();
+        let mut segments = value.split('.');
+        assert_eq!(segments.next().unwrap().decimal().unwrap(), 1);
+        assert_eq!(segments.next().unwrap().decimal().unwrap(), 22);
+        assert_eq!(segments.next().unwrap().decimal().unwrap(), 333);
+        assert_eq!(segments.next(), None);
+
+        let value = Value::new("a--b").unwrap();
+        let segments = value.split('-').map(|v| v.as_str());
+        assert!(segments.eq(["a", "", "b"].iter().copied()));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn split_invalid_separator() {
+        Value::new("ab").unwrap().split(',').for_each(drop);
+    }
+
+    #[test]
+    fn try_from_integer_widths() {
+        let value = Value::new("255").unwrap();
+        assert_eq!(u8::try_from(value).unwrap(), 255);
+        assert_eq!(u16::try_from(&value).unwrap(), 255);
+        assert_eq!(u64::try_from(value).unwrap(), 255);
+        assert_eq!(usize::try_from(&value).unwrap(), 255);
+
+        let value = Value::new("256").unwrap();
+        assert_eq!(
+            u8::try_from(value),
+            Err(Error::ParamValueInvalid(InvalidValue::TooLong))
+        );
+        assert_eq!(u16::try_from(value).unwrap(), 256);
+
+        let value = Value::new("18446744073709551615").unwrap();
+        assert_eq!(u64::try_from(value).unwrap(), u64::MAX);
+
+        let value = Value::new("01").unwrap();
+        assert_eq!(
+            u64::try_from(value),
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat))
+        );
+
+        // Overflow is reported identically for every width
+        let too_long = Error::ParamValueInvalid(InvalidValue::TooLong);
+        assert_eq!(u8::try_from(Value::new("256").unwrap()), Err(too_long));
+        assert_eq!(u16::try_from(Value::new("65536").unwrap()), Err(too_long));
+        assert_eq!(
+            u32::try_from(Value::new("4294967296").unwrap()),
+            Err(too_long)
+        );
+        assert_eq!(
+            u64::try_from(Value::new("18446744073709551616").unwrap()),
+            Err(too_long)
+        );
+    }
+
+    #[test]
+    fn normalized_key() {
+        fn hash(value: &NormalizedValue<'_>) -> u64 {
+            let mut hasher = StableHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a = Value::new("42").unwrap().normalized_key();
+        let b = NormalizedValue::from(Value::new("42").unwrap());
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let c = Value::new("-42").unwrap().normalized_key();
+        assert_ne!(a, c);
+        assert_eq!(c, Value::new("-042").unwrap().normalized_key());
+
+        // Non-canonical encodings of the same integer are equivalent
+        let d = Value::new("042").unwrap().normalized_key();
+        assert_eq!(a, d);
+        assert_eq!(hash(&a), hash(&d));
+        assert_eq!(d.as_value().as_str(), "042");
+        assert_eq!(
+            Value::new("-0").unwrap().normalized_key(),
+            Value::new("000").unwrap().normalized_key()
+        );
+
+        // Anything else is compared by its bytes
+        let e = Value::new("42a").unwrap().normalized_key();
+        assert_ne!(e, Value::new("042a").unwrap().normalized_key());
+        assert_eq!(e, Value::new("42a").unwrap().normalized_key());
+    }
+
+    #[test]
+    fn retain_valid() {
+        let mut buf = *b"a;b\xffc d+";
+        assert_eq!(Value::retain_valid(&mut buf), "abcd+");
+        assert_eq!(Value::retain_valid(&mut []), "");
+        assert_eq!(Value::retain_valid(&mut [b'$'; 8]), "");
+
+        let mut buf = [b'x'; Value::MAX_LENGTH + 8];
+        buf[0] = b';';
+        assert_eq!(Value::retain_valid(&mut buf).len(), Value::MAX_LENGTH);
+    }
+
+    #[test]
+    fn from_percent_encoded() {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        assert_eq!(
+            Value::from_percent_encoded("ab%2Bc%2fd", &mut buf).unwrap(),
+            "ab+c/d"
+        );
+        assert_eq!(Value::from_percent_encoded("", &mut buf).unwrap(), "");
+
+        for &input in &["%", "%2", "%2g", "a%+2b"] {
+            assert_eq!(
+                Value::from_percent_encoded(input, &mut buf),
+                Err(InvalidValue::InvalidFormat.param_error())
+            );
+        }
+
+        assert_eq!(
+            Value::from_percent_encoded("a%3Bb", &mut buf),
+            Err(InvalidValue::InvalidChar(';').param_error())
+        );
+        assert_eq!(
+            Value::from_percent_encoded("%FF", &mut buf),
+            Err(InvalidValue::InvalidChar(char::REPLACEMENT_CHARACTER).param_error())
+        );
+        assert_eq!(
+            Value::from_percent_encoded("abc%2B", &mut [0u8; 3]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 4
+            })
+        );
+    }
+
+    #[test]
+    fn b64_encode_roundtrip() {
+        let original = Value::new("AQIDBAUGBw").unwrap();
+        let mut decoded = [0u8; 16];
+        let decoded = original.b64_decode(&mut decoded).unwrap();
+
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        assert_eq!(Value::b64_encode(decoded, &mut buf).unwrap(), original);
+        assert_eq!(Value::b64_encode(&[], &mut buf).unwrap(), "");
+
+        assert_eq!(
+            Value::b64_encode(&[0u8; 49], &mut buf),
+            Err(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 66
+            }
+            .param_error())
+        );
+        assert_eq!(
+            Value::b64_encode(&[1, 2, 3], &mut [0u8; 3]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 4
+            })
+        );
+    }
+
+    #[test]
+    fn b64_decode_into() {
+        let mut arena = [0u8; 8];
+        let mut cursor = 0;
+
+        for &s in &["AQID", "BAU"] {
+            let value = Value::new(s).unwrap();
+            cursor += value.b64_decode_into(&mut arena[cursor..]).unwrap();
+        }
+
+        assert_eq!(cursor, 5);
+        assert_eq!(&arena[..cursor], [1, 2, 3, 4, 5]);
+        assert!(Value::new("AQID")
+            .unwrap()
+            .b64_decode_into(&mut arena[6..])
+            .is_err());
+    }
+
+    #[test]
+    fn decode_located() {
+        let mut buf = [0u8; 16];
+
+        let value = Value::new("ab.d").unwrap();
+        assert_eq!(
+            value.b64_decode_located(&mut buf),
+            Err((Error::B64Encoding(B64Error::InvalidEncoding), 2))
+        );
+        assert_eq!(
+            value.decode_located(Encoding::Bcrypt, &mut buf).unwrap(),
+            value.decode_with(Encoding::Bcrypt, &mut [0u8; 16]).unwrap()
+        );
+
+        let value = Value::new("abcde").unwrap();
+        assert_eq!(
+            value.b64_decode_located(&mut buf),
+            Err((Error::B64Encoding(B64Error::InvalidLength), 5))
+        );
+
+        let value = Value::new("AAAA").unwrap();
+        assert_eq!(
+            value.b64_decode_located(&mut [0u8; 2]),
+            Err((
+                Error::OutputSize {
+                    provided: Ordering::Less,
+                    expected: 3
+                },
+                0
+            ))
+        );
+        assert_eq!(value.b64_decode_located(&mut buf).unwrap(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn decimal_tolerant() {
+        for &(s, n) in &[("+32", 32), ("32", 32), ("0", 0), ("+4294967295", u32::MAX)] {
+            assert_eq!(Value::new(s).unwrap().decimal_tolerant().unwrap(), n);
+        }
+
+        for &(s, err) in &[
+            ("+0", InvalidValue::InvalidFormat),
+            ("+01", InvalidValue::InvalidFormat),
+            ("01", InvalidValue::InvalidFormat),
+            ("+", InvalidValue::Malformed),
+            ("++1", InvalidValue::InvalidChar('+')),
+            ("+-1", InvalidValue::InvalidChar('-')),
+        ] {
+            assert_eq!(
+                Value::new(s).unwrap().decimal_tolerant(),
+                Err(Error::ParamValueInvalid(err))
+            );
+        }
+
+        assert!(Value::new("+32").unwrap().decimal().is_err());
+    }
+
+    #[test]
+    fn decimal_lenient() {
+        for &(s, n, canonical) in &[
+            ("0", 0, true),
+            ("42", 42, true),
+            ("00", 0, false),
+            ("007", 7, false),
+            ("04294967295", u32::MAX, false),
+        ] {
+            assert_eq!(
+                Value::new(s).unwrap().decimal_lenient().unwrap(),
+                (n, canonical)
+            );
+        }
+
+        for &(s, err) in &[
+            ("", InvalidValue::Malformed),
+            ("0x1", InvalidValue::InvalidChar('x')),
+            ("-1", InvalidValue::InvalidChar('-')),
+            ("004294967296", InvalidValue::TooLong),
+        ] {
+            assert_eq!(
+                Value::new(s).unwrap().decimal_lenient(),
+                Err(Error::ParamValueInvalid(err))
+            );
+        }
+    }
+
+    #[test]
+    fn decimal_in_range() {
+        for &(s, expected) in &[("4", Ok(4)), ("31", Ok(31)), ("12", Ok(12))] {
+            assert_eq!(Value::new(s).unwrap().decimal_in_range(4..=31), expected);
+        }
+
+        for &s in &["0", "3", "32", "4294967295"] {
+            assert_eq!(
+                Value::new(s).unwrap().decimal_in_range(4..=31),
+                Err(InvalidValue::OutOfRange.param_error())
+            );
+        }
+
+        assert_eq!(
+            Value::new("04").unwrap().decimal_in_range(4..=31),
+            Err(InvalidValue::InvalidFormat.param_error())
+        );
+    }
+
+    #[test]
+    fn decimal_checked_mul() {
+        let value = Value::new("65536").unwrap();
+        assert_eq!(value.decimal_checked_mul(1024).unwrap(), 67_108_864);
+        assert_eq!(value.decimal_checked_mul(0).unwrap(), 0);
+        assert_eq!(
+            value.decimal_checked_mul(65536),
+            Err(Error::ParamValueInvalid(InvalidValue::TooLong))
+        );
+        assert_eq!(
+            Value::new("01").unwrap().decimal_checked_mul(2),
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidFormat))
+        );
+    }
+
+    #[test]
+    fn eq_decimal() {
+        let value = Value::new("32").unwrap();
+        assert_eq!(value, 32);
+        assert_eq!(32, value);
+        assert_ne!(value, 33);
+        assert_ne!(Value::new("032").unwrap(), 32);
+        assert_ne!(Value::new("abc").unwrap(), 0);
+        assert_ne!(Value::new("-32").unwrap(), 32);
+    }
+
+    #[test]
+    fn decimal_or() {
+        assert_eq!(Value::new("42").unwrap().decimal_or(7), 42);
+        assert_eq!(Value::new("x").unwrap().decimal_or(7), 7);
+
+        let fallback = |err| match err {
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(_)) => 1,
+            _ => 2,
+        };
+        assert_eq!(Value::new("42").unwrap().decimal_or_else(fallback), 42);
+        assert_eq!(Value::new("x").unwrap().decimal_or_else(fallback), 1);
+        assert_eq!(Value::new("01").unwrap().decimal_or_else(fallback), 2);
+    }
+
+    #[test]
+    fn ascii_invariant() {
+        for &s in &["", "abc", Value::VALID_CHARS] {
+            let value = Value::new_with_max(s, s.len()).unwrap();
+            assert!(value.is_ascii());
+            assert_eq!(value.char_count(), value.len());
+            assert_eq!(value.char_count(), s.chars().count());
+        }
+    }
+
+    #[test]
+    fn validate_chars() {
+        let digits_and_dots = |c: char| c.is_ascii_digit() || c == '.';
+        let value = Value::new("1.2.3").unwrap();
+        assert_eq!(value.validate_chars(digits_and_dots), Ok(()));
+        assert_eq!(Value::new("").unwrap().validate_chars(|_| false), Ok(()));
+        assert_eq!(
+            Value::new("1.2-3+4")
+                .unwrap()
+                .validate_chars(digits_and_dots),
+            Err(InvalidValue::InvalidChar('-').param_error())
+        );
+    }
+
+    #[test]
+    fn into_str() {
+        let input = "abc";
+        let s: &'static str = Value::new(input).unwrap().into_str();
+        assert_eq!(s, input);
+    }
+
+    #[test]
+    fn chars_and_bytes() {
+        let value = Value::new("a+B/9").unwrap();
+        assert!(value.chars().eq("a+B/9".chars()));
+        assert!(value.bytes().eq(b"a+B/9".iter().copied()));
+        assert_eq!(Value::new("").unwrap().chars().next(), None);
+    }
+
+    #[test]
+    fn stable_hash() {
+        // FNV-1a test vectors
+        assert_eq!(Value::EMPTY.stable_hash(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(
+            Value::new("a").unwrap().stable_hash(),
+            0xaf63_dc4c_8601_ec8c
+        );
+        assert_eq!(
+            Value::new("foobar").unwrap().stable_hash(),
+            0x8594_4171_f739_67e8
+        );
+        assert_ne!(
+            Value::new("65536").unwrap().stable_hash(),
+            Value::new("65537").unwrap().stable_hash()
+        );
+    }
+
+    #[test]
+    fn try_fold_bytes() {
+        let value = Value::new("a+B/9").unwrap();
+        let sum = value.try_fold_bytes(0u32, |acc, b| Ok::<_, ()>(acc + u32::from(b)));
+        assert_eq!(sum, Ok(b"a+B/9".iter().map(|&b| u32::from(b)).sum()));
+
+        let mut seen = 0;
+        let result = value.try_fold_bytes((), |(), b| {
+            seen += 1;
+            if b == b'B' {
+                Err(b)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err(b'B'));
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn value_eq() {
+        let a = Value::new("42").unwrap();
+        assert!(a.value_eq(&Value::new("42").unwrap()));
+        assert!(!a.value_eq(&Value::new("-42").unwrap()));
+        assert!(!a.value_eq(&Value::new("43").unwrap()));
+        assert!(Value::new("-0")
+            .unwrap()
+            .value_eq(&Value::new("0").unwrap()));
+        assert!(Value::new("abc")
+            .unwrap()
+            .value_eq(&Value::new("abc").unwrap()));
+        assert!(!Value::new("abc")
+            .unwrap()
+            .value_eq(&Value::new("ABC").unwrap()));
+
+        // A non-canonical source is equivalent to, but not equal to, its
+        // re-serialized form
+        let source = Value::new("0065536").unwrap();
+        let mut buf = [0u8; 10];
+        let reserialized =
+            Value::from_decimal(source.decimal_lenient().unwrap().0, &mut buf).unwrap();
+        assert!(source.value_eq(&reserialized));
+        assert_ne!(source, reserialized);
+    }
+
+    #[test]
+    fn new_unquoted() {
+        for &input in &["a+b", "\"a+b\"", "'a+b'"] {
+            assert_eq!(Value::new_unquoted(input).unwrap(), "a+b");
+        }
+
+        assert_eq!(Value::new_unquoted("\"\"").unwrap(), "");
+        assert_eq!(Value::new_unquoted("").unwrap(), "");
+
+        for &input in &["\"", "\"a+b", "a+b'", "\"a+b'"] {
+            assert_eq!(
+                Value::new_unquoted(input),
+                Err(InvalidValue::InvalidFormat.param_error()),
+                "{}",
+                input
+            );
+        }
+
+        // Only a single pair of quotes is removed
+        assert_eq!(
+            Value::new_unquoted("\"'a'\""),
+            Err(InvalidValue::InvalidChar('\'').param_error())
+        );
+        assert!(Value::new("\"a+b\"").is_err());
+    }
+
+    #[test]
+    fn new_decimal() {
+        for &s in &["0", "7", "-7", "4294967296", "-99999999999999999999"] {
+            assert_eq!(Value::new_decimal(s).unwrap().as_str(), s);
+        }
+
+        for &(s, err) in &[
+            ("007", InvalidValue::InvalidFormat),
+            ("-0", InvalidValue::InvalidFormat),
+            ("-", InvalidValue::Malformed),
+            ("", InvalidValue::Malformed),
+            ("1a", InvalidValue::InvalidChar('a')),
+            ("x;y", InvalidValue::InvalidChar(';')),
+        ] {
+            assert_eq!(Value::new_decimal(s), Err(Error::ParamValueInvalid(err)));
+        }
+    }
+
+    #[test]
+    fn new_lossy() {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        assert_eq!(
+            Value::new_lossy("a;b c\u{e9}d", &mut buf).unwrap().as_str(),
+            "abcd"
+        );
+        assert_eq!(Value::new_lossy(";;", &mut buf).unwrap().as_str(), "");
+
+        let input = [b'x'; Value::MAX_LENGTH + 8];
+        let input = core::str::from_utf8(&input).unwrap();
+        let value = Value::new_lossy(input, &mut buf).unwrap();
+        assert_eq!(value.len(), Value::MAX_LENGTH);
+
+        assert_eq!(
+            Value::new_lossy("a;bc", &mut [0u8; 2]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn new_salt() {
+        assert_eq!(Value::new_salt("saltsaltsalt").unwrap(), "saltsaltsalt");
+        assert_eq!(
+            Value::new_salt("abc"),
+            Err(Error::SaltInvalid(InvalidValue::TooShort))
+        );
+        assert_eq!(
+            Value::new_salt("salt;salt"),
+            Err(Error::SaltInvalid(InvalidValue::InvalidChar(';')))
+        );
+        assert_eq!(
+            Value::new_salt(INVALID_TOO_LONG),
+            Err(Error::SaltInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            }))
+        );
+    }
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(Value::from_bytes(b"a+b/c").unwrap(), "a+b/c");
+        assert_eq!(Value::from_bytes(b"").unwrap(), "");
+
+        for &(bytes, c) in &[
+            (&b"x;y"[..], ';'),
+            (b"a\xe9", char::REPLACEMENT_CHARACTER),
+            (b"\xff\xfe", char::REPLACEMENT_CHARACTER),
+        ] {
+            assert_eq!(
+                Value::from_bytes(bytes),
+                Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(c)))
+            );
+        }
+
+        assert_eq!(
+            Value::from_bytes(INVALID_TOO_LONG.as_bytes()),
+            Value::new(INVALID_TOO_LONG)
+        );
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        fn parse<'a, T: TryInto<Value<'a>, Error = Error>>(input: T) -> Result<Value<'a>, Error> {
+            input.try_into()
+        }
+
+        assert_eq!(parse(&b"abc"[..]).unwrap(), "abc");
+        assert_eq!(parse(b"abc").unwrap(), "abc");
+        assert_eq!(parse("abc").unwrap(), "abc");
+        assert_eq!(
+            parse(b"x;y"),
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(';')))
+        );
+    }
+
+    #[test]
+    fn parse_prefix() {
+        let (value, rest) = Value::parse_prefix("m=4096,t=3").unwrap();
+        assert_eq!(value.as_str(), "m");
+        assert_eq!(rest, "=4096,t=3");
+
+        let (value, rest) = Value::parse_prefix("4096,t=3").unwrap();
+        assert_eq!(value.as_str(), "4096");
+        assert_eq!(rest, ",t=3");
+
+        let (value, rest) = Value::parse_prefix("abc").unwrap();
+        assert_eq!((value.as_str(), rest), ("abc", ""));
+
+        let (value, rest) = Value::parse_prefix("$abc").unwrap();
+        assert_eq!((value.as_str(), rest), ("", "$abc"));
+
+        let (value, rest) = Value::parse_prefix("ab\u{e9}").unwrap();
+        assert_eq!((value.as_str(), rest), ("ab", "\u{e9}"));
+
+        assert_eq!(
+            Value::parse_prefix(INVALID_TOO_LONG),
+            Err(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            }
+            .param_error())
+        );
+    }
+
+    #[test]
+    fn eq_str() {
+        let value = Value::new("foo").unwrap();
+        assert_eq!(value, "foo");
+        assert_eq!("foo", value);
+        assert_eq!(value, *"foo");
+        assert_eq!(*"foo", value);
+        assert_ne!(value, "Foo");
+        assert_ne!("foo.", value);
+        assert_eq!(value, Value::new("foo").unwrap());
+    }
+
+    #[test]
+    fn cmp_numeric() {
+        let nine = Value::new("9").unwrap();
+        let ten = Value::new("10").unwrap();
+        assert!(ten < nine);
+        assert_eq!(nine.cmp_numeric(&ten), Some(Ordering::Less));
+        assert_eq!(ten.cmp_numeric(&nine), Some(Ordering::Greater));
+        assert_eq!(ten.cmp_numeric(&ten), Some(Ordering::Equal));
+        assert_eq!(
+            Value::new("-10").unwrap().cmp_numeric(&nine),
+            Some(Ordering::Less)
+        );
+        assert_eq!(nine.cmp_numeric(&Value::new("09").unwrap()), None);
+        assert_eq!(Value::new("a").unwrap().cmp_numeric(&nine), None);
+    }
+
+    #[test]
+    fn value_writer() {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        let value = ValueWriter::new(&mut buf)
+            .push_decimal(0)
+            .push_sep('-')
+            .push_str("")
+            .push_str("a+b")
+            .finish()
+            .unwrap();
+        assert_eq!(value, "0-a+b");
+        assert_eq!(ValueWriter::new(&mut []).finish().unwrap(), "");
+
+        // The first error is reported, and later parts are ignored
+        let err = ValueWriter::new(&mut buf)
+            .push_str("a")
+            .push_sep(',')
+            .push_str(INVALID_CHAR)
+            .finish();
+        assert_eq!(err, Err(InvalidValue::InvalidChar(',').param_error()));
+
+        let err = ValueWriter::new(&mut buf)
+            .push_str(INVALID_CHAR)
+            .push_decimal(1)
+            .finish();
+        assert_eq!(err, Err(InvalidValue::InvalidChar(';').param_error()));
+
+        let mut long = [0u8; 128];
+        let err = ValueWriter::new(&mut long)
+            .push_str(&INVALID_TOO_LONG[..60])
+            .push_decimal(12345)
+            .finish();
+        assert_eq!(err, Err(InvalidValue::too_long(64, 65).param_error()));
+
+        let mut short = [0u8; 4];
+        let err = ValueWriter::new(&mut short)
+            .push_str("abc")
+            .push_decimal(10)
+            .finish();
+        assert_eq!(
+            err,
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 5
+            })
+        );
+    }
+
+    #[test]
+    fn join() {
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        let value = Value::join(&["a", "bb", "ccc"], '.', &mut buf).unwrap();
+        assert_eq!(value, "a.bb.ccc");
+        assert!(value.split('.').eq(["a", "bb", "ccc"].iter().copied()));
+
+        assert_eq!(Value::join(&[], '.', &mut buf).unwrap(), "");
+        assert_eq!(Value::join(&["a"], '.', &mut buf).unwrap(), "a");
+
+        assert_eq!(
+            Value::join(&["a", "b"], ',', &mut buf),
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(',')))
+        );
+        assert_eq!(
+            Value::join(&["a", INVALID_CHAR], '.', &mut buf),
+            Err(Error::ParamValueInvalid(InvalidValue::InvalidChar(';')))
+        );
+        assert_eq!(
+            Value::join(&["a", "bc"], '-', &mut [0u8; 3]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 4
+            })
+        );
+
+        let half = &INVALID_TOO_LONG[..(Value::MAX_LENGTH / 2)];
+        assert_eq!(
+            Value::join(&[half, half], '-', &mut buf),
+            Err(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            }
+            .param_error())
+        );
+    }
+
+    #[test]
+    fn classify() {
+        for &(s, kind) in &[
+            ("0", ValueKind::Decimal),
+            ("1234", ValueKind::Decimal),
+            ("-42", ValueKind::Decimal),
+            ("99999999999999999999", ValueKind::Decimal),
+            ("0123", ValueKind::MaybeB64),
+            ("AQID", ValueKind::MaybeB64),
+            ("a+b/", ValueKind::MaybeB64),
+            ("-0", ValueKind::Text),
+            ("abcde", ValueKind::Text),
+            ("a.b", ValueKind::Text),
+            ("", ValueKind::Text),
+        ] {
+            assert_eq!(Value::new(s).unwrap().classify(), kind, "{}", s);
+        }
+    }
+
+    #[test]
+    fn is_b64() {
+        for &s in &["", "AQ", "AQI", "AQID", "a+b/"] {
+            let value = Value::new(s).unwrap();
+            assert!(value.is_b64(), "{}", s);
+
+            let mut buf = [0u8; 3];
+            let len = value.b64_decoded_len().unwrap();
+            assert!(value.b64_decode(&mut buf[..len]).is_ok());
+        }
+
+        for &s in &["A", "AQIDB", "a.b", "a-b", "AB", "AR", "AAB"] {
+            let value = Value::new(s).unwrap();
+            assert!(!value.is_b64(), "{}", s);
+            assert!(value.b64_decode(&mut [0u8; 8]).is_err());
+        }
+    }
+
+    #[test]
+    fn has_ambiguous_chars() {
+        for &s in &["OOo", "lI", "O0", "moon0", "x1xl", "I1"] {
+            assert!(Value::new(s).unwrap().has_ambiguous_chars(), "{}", s);
+        }
+
+        // Repeated characters, or characters from different groups, aren't
+        // ambiguous
+        for &s in &["", "abc", "1000", "0I", "o1"] {
+            assert!(!Value::new(s).unwrap().has_ambiguous_chars(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn decimal_radix() {
+        for &(s, radix, i) in &[
+            ("0", 16, 0),
+            ("ff", 16, 255),
+            ("FFFFFFFF", 16, u32::MAX),
+            ("777", 8, 511),
+            ("101", 2, 5),
+        ] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.decimal_radix(radix).unwrap(), i);
+        }
+
+        for &(s, radix, err) in &[
+            ("0ff", 16, InvalidValue::InvalidFormat),
+            ("100000000", 16, InvalidValue::TooLong),
+            ("8", 8, InvalidValue::InvalidChar('8')),
+            ("fg", 16, InvalidValue::InvalidChar('g')),
+            ("", 16, InvalidValue::Malformed),
+        ] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(
+                value.decimal_radix(radix),
+                Err(Error::ParamValueInvalid(err))
+            );
+        }
+
+        for &s in &["0", "01", "4294967295", "4294967296", "1a", ""] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.decimal_radix(10), value.decimal());
+        }
+    }
+
+    #[test]
+    fn signed_decimal_value() {
+        let valid_decimals = &[
+            ("0", 0i64),
+            ("1", 1),
+            ("-1", -1),
+            ("-4294967296", -4294967296),
+            ("9223372036854775807", i64::MAX),
+            ("-9223372036854775808", i64::MIN),
+        ];
+
+        for &(s, i) in valid_decimals {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.decimal_signed().unwrap(), i);
+        }
+    }
+
+    #[test]
+    fn reject_malformed_signed_decimal() {
+        for &(s, expected) in &[
+            ("", InvalidValue::Malformed),
+            ("-", InvalidValue::Malformed),
+            ("-0", InvalidValue::InvalidFormat),
+            ("-01", InvalidValue::InvalidFormat),
+            ("01", InvalidValue::InvalidFormat),
+            ("--1", InvalidValue::InvalidChar('-')),
+            ("1-", InvalidValue::InvalidChar('-')),
+        ] {
+            let value = Value::new(s).unwrap();
+            let err = value.decimal_signed().err().unwrap();
+            assert_eq!(err, Error::ParamValueInvalid(expected));
+        }
+    }
+
+    #[test]
+    fn reject_overlong_signed_decimal() {
+        for &s in &["9223372036854775808", "-9223372036854775809"] {
+            let value = Value::new(s).unwrap();
+            let err = value.decimal_signed().err().unwrap();
+            assert_eq!(err, Error::ParamValueInvalid(InvalidValue::TooLong));
+        }
+    }
+
+    //
+    // B64 decoding tests
+    //
+
+    #[test]
+    fn b64_decoded_len() {
+        for &(s, len) in &[("", 0), ("AQ", 1), ("AQI", 2), ("AQID", 3), ("AQIDBA", 4)] {
+            let value = Value::new(s).unwrap();
+            assert_eq!(value.b64_decoded_len().unwrap(), len);
+
+            let mut buf = [0u8; 8];
+            assert_eq!(value.b64_decode(&mut buf).unwrap().len(), len);
+        }
+
+        let err = Value::new("AQIDB")
+            .unwrap()
+            .b64_decoded_len()
+            .err()
+            .unwrap();
+        assert_eq!(err, Error::B64Encoding(B64Error::InvalidLength));
+    }
+
+    #[test]
+    fn b64_decode_buffer_too_small() {
+        let value = Value::new("AQID").unwrap();
+        let err = value.b64_decode(&mut [0u8; 2]).err().unwrap();
+        assert_eq!(err, Error::B64Encoding(B64Error::InvalidLength));
+        assert_eq!(value.b64_decode(&mut [0u8; 3]).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn b64_decode_exact() {
+        let value = Value::new("AQID").unwrap();
+        let mut buf = [0u8; 3];
+        value.b64_decode_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3]);
+
+        let mut buf = [0u8; 4];
+        assert_eq!(
+            value.b64_decode_exact(&mut buf),
+            Err(Error::OutputSize {
+                provided: Ordering::Greater,
+                expected: 3
+            })
+        );
+        assert_eq!(buf, [0; 4]);
+        assert_eq!(
+            value.b64_decode_exact(&mut [0u8; 2]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 3
+            })
+        );
+
+        assert_eq!(Value::EMPTY.b64_decode_exact(&mut []), Ok(()));
+        assert!(matches!(
+            Value::new("A").unwrap().b64_decode_exact(&mut []),
+            Err(Error::B64Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn b64_decode_array() {
+        let value = Value::new("AQID").unwrap();
+        assert_eq!(value.b64_decode_array::<3>().unwrap(), [1, 2, 3]);
+
+        assert_eq!(
+            value.b64_decode_array::<2>(),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            value.b64_decode_array::<4>(),
+            Err(Error::OutputSize {
+                provided: Ordering::Greater,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn b64_decode_u64_be() {
+        assert_eq!(Value::new("").unwrap().b64_decode_u64_be(), Ok(0));
+        assert_eq!(
+            Value::new("AQID").unwrap().b64_decode_u64_be(),
+            Ok(0x010203)
+        );
+        assert_eq!(
+            Value::new("AQIDBAUGBwg").unwrap().b64_decode_u64_be(),
+            Ok(0x0102_0304_0506_0708)
+        );
+        assert_eq!(
+            Value::new("AQIDBAUGBwgJ").unwrap().b64_decode_u64_be(),
+            Err(Error::OutputSize {
+                provided: Ordering::Greater,
+                expected: 8
+            })
+        );
+        assert!(matches!(
+            Value::new("A").unwrap().b64_decode_u64_be(),
+            Err(Error::B64Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn from_decimal() {
+        let mut buf = [0u8; decimal::MAX_DECIMAL_DIGITS];
+        assert_eq!(decimal::MAX_DECIMAL_DIGITS, 10);
+
+        for &n in &[0, 1, 10, 19, 65536, Decimal::MAX] {
+            let value = Value::from_decimal(n, &mut buf).unwrap();
+            assert_eq!(value.decimal(), Ok(n));
+            assert_eq!(value, ValueBuf::from(n).as_value());
+        }
+
+        assert_eq!(
+            Value::from_decimal(4_294_967_295, &mut buf).unwrap(),
+            "4294967295"
+        );
+        assert_eq!(Value::from_decimal(7, &mut [0u8; 1]).unwrap(), "7");
+
+        assert_eq!(
+            Value::from_decimal(100, &mut [0u8; 2]),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn b64_decode_empty() {
+        // An empty value is valid B64 which decodes to no bytes, regardless
+        // of the size of the output buffer
+        for buf in &mut [&mut [][..], &mut [0u8; 1][..], &mut [0u8; 64][..]] {
+            assert_eq!(Value::EMPTY.b64_decode(buf), Ok(&[][..]));
+            assert_eq!(Value::EMPTY.b64_decode_into(buf), Ok(0));
+            assert_eq!(Value::EMPTY.b64_decode_located(buf), Ok(&[][..]));
+        }
+
+        assert_eq!(Value::EMPTY.b64_decoded_len(), Ok(0));
+        assert_eq!(Value::EMPTY.b64_decode_exact(&mut []), Ok(()));
+        assert_eq!(Value::EMPTY.b64_decode_array::<0>(), Ok([]));
+        assert_eq!(Value::EMPTY.b64_decode_u64_be(), Ok(0));
+
+        let mut buf = [0u8; 4];
+        let encoded = Value::b64_encode(&[], &mut buf).unwrap();
+        assert_eq!(encoded, Value::EMPTY);
+        assert_eq!(encoded.b64_decode(&mut [0u8; 4]), Ok(&[][..]));
+    }
+
+    #[test]
+    fn decode_with_mcf_encodings() {
+        let mut buf = [0u8; 8];
+
+        // bcrypt: `./A-Za-z0-9`
+        let value = Value::new("..").unwrap();
+        assert_eq!(value.decode_with(Encoding::Bcrypt, &mut buf).unwrap(), [0]);
+
+        // crypt(3): `./0-9A-Za-z`
+        let value = Value::new("./2").unwrap();
+        assert_eq!(
+            value.decode_with(Encoding::Crypt, &mut buf).unwrap(),
+            [0x00, 0x11]
+        );
+
+        // `+` is valid B64 but not part of either MCF alphabet
+        let value = Value::new("AB+A").unwrap();
+        assert!(value.decode_with(Encoding::B64, &mut buf).is_ok());
+        for &encoding in &[Encoding::Bcrypt, Encoding::Crypt] {
+            let err = value.decode_with(encoding, &mut buf).err().unwrap();
+            assert_eq!(err, Error::B64Encoding(B64Error::InvalidEncoding));
+        }
+    }
+
+    #[test]
+    fn decode_with_base64_standard() {
+        let mut buf = [0u8; 8];
+        let value = Value::new("AQI").unwrap();
+        assert_eq!(
+            value
+                .decode_with(Encoding::Base64Standard, &mut buf)
+                .unwrap(),
+            [1, 2]
+        );
+
+        // Padding can't occur in a value
+        assert!(Value::new("AQI=").is_err());
+    }
+
+    #[test]
+    fn hex_decode() {
+        let mut buf = [0u8; 8];
+        let value = Value::new("00ff10Ab").unwrap();
+        assert_eq!(
+            value.hex_decode(&mut buf).unwrap(),
+            [0x00, 0xff, 0x10, 0xab]
+        );
+
+        let err = Value::new("abc")
+            .unwrap()
+            .hex_decode(&mut buf)
+            .err()
+            .unwrap();
+        assert_eq!(err, Error::B64Encoding(B64Error::InvalidLength));
+
+        let err = Value::new("zz")
+            .unwrap()
+            .hex_decode(&mut buf)
+            .err()
+            .unwrap();
+        assert_eq!(err, Error::B64Encoding(B64Error::InvalidEncoding));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn b64_decode_vec() {
+        let value = Value::new("AQID").unwrap();
+        assert_eq!(value.b64_decode_vec().unwrap(), [1, 2, 3]);
+
+        let value = Value::new("AQI").unwrap();
+        assert_eq!(value.b64_decode_vec().unwrap(), [1, 2]);
+
+        let max = "A".repeat(Value::MAX_LENGTH);
+        let value = Value::new(&max).unwrap();
+        assert_eq!(value.b64_decode_vec().unwrap().len(), 48);
+
+        assert!(Value::new("A").unwrap().b64_decode_vec().is_err());
+    }
+
+    //
+    // String parsing tests
+    //
+
+    #[test]
+    fn empty() {
+        assert!(Value::EMPTY.is_empty());
+        assert_eq!(Value::EMPTY, Value::new("").unwrap());
+        assert_eq!(Value::EMPTY, Value::default());
+        assert_eq!(Value::EMPTY, "");
+        assert_ne!(Value::EMPTY, Value::new("0").unwrap());
+        assert_ne!(Some(Value::EMPTY), None);
+    }
+
+    #[test]
+    fn string_value() {
+        let valid_examples = [
+            "",
+            "X",
+            "x",
+            "xXx",
+            "a+b.c-d",
+            "1/2",
+            "01234567891123456789212345678931",
+        ];
+
+        for &example in &valid_examples {
+            let value = Value::new(example).unwrap();
+            assert_eq!(value.as_str(), example);
+        }
+    }
+
+    #[test]
+    fn const_value() {
+        const VALUE: Value<'static> = Value::new_const("a+b.c-d");
+        assert_eq!(VALUE, Value::new("a+b.c-d").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid PHC value character")]
+    fn const_reject_invalid_char() {
+        Value::new_const(INVALID_CHAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "PHC value string too long")]
+    fn const_reject_too_long() {
+        Value::new_const(INVALID_TOO_LONG);
+    }
+
+    #[test]
+    fn valid_chars() {
+        for b in 0..=0xFFu8 {
+            let c = char::from(b);
+            assert_eq!(Value::is_valid_char(c), Value::VALID_CHARS.contains(c));
+            assert_eq!(super::is_byte_valid(b), Value::is_valid_char(c));
+        }
+
+        assert!(Value::new_with_max(Value::VALID_CHARS, Value::VALID_CHARS.len()).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn validate_all() {
+        let values = Value::validate_all(["a", "b+c", ""]).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[1].as_str(), "b+c");
+
+        let (i, err) = Value::validate_all(["a", INVALID_CHAR, INVALID_TOO_LONG])
+            .err()
+            .unwrap();
+        assert_eq!(i, 1);
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(';'))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn value_interner() {
+        let mut interner = ValueInterner::new();
+        assert!(interner.is_empty());
+
+        assert_eq!(interner.intern("65536").unwrap(), "65536");
+        assert_eq!(interner.intern("65536").unwrap(), "65536");
+        assert_eq!(interner.intern("3").unwrap(), "3");
+        assert_eq!(interner.len(), 2);
+
+        let a = interner.get("65536").unwrap();
+        let b = interner.get("65536").unwrap();
+        assert!(core::ptr::eq(a.as_str(), b.as_str()));
+        assert_eq!(interner.get("3").unwrap(), "3");
+        assert_eq!(interner.get("4"), None);
+
+        assert_eq!(
+            interner.intern(INVALID_CHAR),
+            Err(InvalidValue::InvalidChar(';').param_error())
+        );
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn validate_verbose() {
+        assert_eq!(Value::validate_verbose("a+b").unwrap(), "a+b");
+
+        let input = "a;b!".repeat(17);
+        let errors = Value::validate_verbose(&input).err().unwrap();
+        assert_eq!(errors.len(), 35);
+        assert_eq!(
+            errors[0],
+            (
+                Value::MAX_LENGTH,
+                InvalidValue::MaxLengthExceeded {
+                    max: 64,
+                    actual: 68
+                }
+                .param_error()
+            )
+        );
+        assert_eq!(errors[1], (1, InvalidValue::InvalidChar(';').param_error()));
+        assert_eq!(errors[2], (3, InvalidValue::InvalidChar('!').param_error()));
+        assert_eq!(errors[34].0, 67);
+    }
+
+    #[test]
+    fn reject_invalid_char() {
+        let err = Value::new(INVALID_CHAR).err().unwrap();
+        assert!(matches!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(_))
+        ));
+
+        // Non-ASCII characters are reported in full, at their byte offset
+        assert_eq!(
+            Value::new_located("ab\u{20ac}c"),
+            Err((InvalidValue::InvalidChar('\u{20ac}').param_error(), 2))
+        );
     }
 
     #[test]
     fn reject_too_long() {
         let err = Value::new(INVALID_TOO_LONG).err().unwrap();
-        assert_eq!(err, Error::ParamValueInvalid(InvalidValue::TooLong));
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
+
+        match err {
+            Error::ParamValueInvalid(err) => assert!(err.is_too_long()),
+            _ => unreachable!(),
+        }
+        assert!(InvalidValue::TooLong.is_too_long());
+        assert!(!InvalidValue::TooShort.is_too_long());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn too_long_display() {
+        use alloc::string::ToString;
+
+        let err = Value::new(INVALID_TOO_LONG).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "invalid parameter value: value is 65 bytes, maximum is 64"
+        );
+
+        let err = Value::new_salt(INVALID_TOO_LONG).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "salt invalid: value is 65 bytes, maximum is 64"
+        );
+    }
+
+    #[test]
+    fn new_with_max() {
+        let value = Value::new_with_max(INVALID_TOO_LONG, 65).unwrap();
+        assert_eq!(value.as_str(), INVALID_TOO_LONG);
+
+        let err = Value::new_with_max("abcd", 3).err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::MaxLengthExceeded { max: 3, actual: 4 })
+        );
+
+        let err = Value::new_with_max(INVALID_CHAR, 65).err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(';'))
+        );
+    }
+
+    #[test]
+    fn new_located() {
+        let value = Value::new_located("a+b.c-d").unwrap();
+        assert_eq!(value.as_str(), "a+b.c-d");
+
+        let (err, offset) = Value::new_located(INVALID_CHAR).err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(';'))
+        );
+        assert_eq!(offset, 1);
+
+        let (err, offset) = Value::new_located("ab\u{e9}$").err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar('\u{e9}'))
+        );
+        assert_eq!(offset, 2);
+
+        let (err, offset) = Value::new_located(INVALID_TOO_LONG).err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
+        assert_eq!(offset, Value::MAX_LENGTH);
+    }
+
+    #[test]
+    fn check_len() {
+        assert_eq!(super::check_len(0, 0), Ok(()));
+        assert_eq!(super::check_len(64, 64), Ok(()));
+        assert_eq!(
+            super::check_len(65, 64),
+            Err(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
+        assert_eq!(
+            super::check_len(65, 64),
+            Err(InvalidValue::too_long(64, 65))
+        );
+    }
+
+    #[test]
+    fn too_long_stores_lengths() {
+        // The factory doesn't validate its arguments, so callers with an
+        // unusual notion of length can't trip a panic.
+        assert_eq!(
+            InvalidValue::too_long(10, 3),
+            InvalidValue::MaxLengthExceeded { max: 10, actual: 3 }
+        );
+        assert!(InvalidValue::too_long(10, 3).is_too_long());
     }
 
     #[test]
     fn reject_invalid_char_and_too_long() {
         let err = Value::new(INVALID_CHAR_AND_TOO_LONG).err().unwrap();
-        assert_eq!(err, Error::ParamValueInvalid(InvalidValue::TooLong));
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
+    }
+
+    #[test]
+    fn ct_eq() {
+        use subtle::ConstantTimeEq;
+
+        let a = Value::new("keyid").unwrap();
+        assert!(bool::from(a.ct_eq(&Value::new("keyid").unwrap())));
+        assert!(!bool::from(a.ct_eq(&Value::new("keyie").unwrap())));
+        assert!(!bool::from(a.ct_eq(&Value::new("keyi").unwrap())));
+        assert!(!bool::from(a.ct_eq(&Value::new("").unwrap())));
+        assert!(bool::from(
+            Value::new("").unwrap().ct_eq(&Value::new("").unwrap())
+        ));
+    }
+
+    #[test]
+    fn write_to() {
+        let value = Value::new("a+b.c-d").unwrap();
+        let mut buf = [0u8; Value::MAX_LENGTH];
+        assert_eq!(value.write_to(&mut buf).unwrap(), "a+b.c-d");
+
+        let err = value.write_to(&mut buf[..6]).err().unwrap();
+        assert_eq!(
+            err,
+            Error::OutputSize {
+                provided: core::cmp::Ordering::Less,
+                expected: 7
+            }
+        );
+    }
+
+    #[test]
+    fn to_padded() {
+        let (buf, len) = Value::new("a+b").unwrap().to_padded().unwrap();
+        assert_eq!(len, 3);
+        assert_eq!(&buf[..3], b"a+b");
+        assert!(buf[3..].iter().all(|&b| b == 0));
+
+        let (buf, len) = Value::new(INVALID_TOO_LONG.split_at(64).0)
+            .unwrap()
+            .to_padded()
+            .unwrap();
+        assert_eq!((&buf[..], len), (&INVALID_TOO_LONG.as_bytes()[..64], 64));
+
+        let long = Value::new_with_max(INVALID_TOO_LONG, 65).unwrap();
+        assert_eq!(
+            long.to_padded(),
+            Err(Error::OutputSize {
+                provided: Ordering::Less,
+                expected: 65
+            })
+        );
+    }
+
+    //
+    // Case-insensitivity tests
+    //
+
+    #[test]
+    fn common_prefix_len() {
+        let a = Value::new("AQIDBA").unwrap();
+        assert_eq!(a.common_prefix_len(&Value::new("AQIxyz").unwrap()), 3);
+        assert_eq!(a.common_prefix_len(&Value::new("AQ").unwrap()), 2);
+        assert_eq!(a.common_prefix_len(&a), a.len());
+        assert_eq!(a.common_prefix_len(&Value::new("").unwrap()), 0);
+        assert_eq!(a.common_prefix_len(&Value::new("aQIDBA").unwrap()), 0);
+    }
+
+    #[test]
+    fn eq_ignore_ascii_case() {
+        let a = Value::new("Argon2-ID/x+y.z").unwrap();
+        let b = Value::new("argon2-id/X+Y.Z").unwrap();
+        assert_ne!(a, b);
+        assert!(a.eq_ignore_ascii_case(&b));
+        assert!(!a.eq_ignore_ascii_case(&Value::new("argon2-id").unwrap()));
+    }
+
+    #[test]
+    fn to_ascii_lowercase_into() {
+        let value = Value::new("Argon2-ID/X+Y.Z").unwrap();
+        let mut buf = [0u8; 32];
+        let lower = value.to_ascii_lowercase_into(&mut buf).unwrap();
+        assert_eq!(lower.as_str(), "argon2-id/x+y.z");
+
+        let err = value.to_ascii_lowercase_into(&mut [0u8; 4]).err().unwrap();
+        assert_eq!(
+            err,
+            Error::OutputSize {
+                provided: core::cmp::Ordering::Less,
+                expected: value.len()
+            }
+        );
+    }
+
+    //
+    // `ValueBuf` tests
+    //
+
+    #[test]
+    fn value_buf_parse() {
+        let value_buf = "a+b.c-d".parse::<ValueBuf>().unwrap();
+        assert_eq!(value_buf.as_str(), "a+b.c-d");
+        assert_eq!(value_buf.as_value(), Value::new("a+b.c-d").unwrap());
+        assert_eq!(Value::from(&value_buf).len(), 7);
+
+        let value_buf = ValueBuf::new("32").unwrap();
+        assert_eq!(value_buf.as_value().decimal().unwrap(), 32);
+    }
+
+    #[test]
+    fn value_buf_outlives_source() {
+        let value_buf = {
+            let source = [b'v', b'1', b'.', b'x'];
+            let value = Value::new(core::str::from_utf8(&source).unwrap()).unwrap();
+            ValueBuf::try_from(value).unwrap()
+        };
+
+        assert_eq!(value_buf.as_str(), "v1.x");
+    }
+
+    #[test]
+    fn value_buf_from_decimal_and_bytes() {
+        assert_eq!(ValueBuf::from(0).as_str(), "0");
+        assert_eq!(ValueBuf::from(u32::MAX).as_value().decimal(), Ok(u32::MAX));
+
+        let value_buf = ValueBuf::b64_encode(&[1, 2, 3]).unwrap();
+        let copy = value_buf;
+        assert_eq!(value_buf.as_str(), "AQID");
+        assert_eq!(copy, value_buf);
+        assert!(ValueBuf::b64_encode(&[0u8; 49]).is_err());
+    }
+
+    #[test]
+    fn value_buf_ord() {
+        let a = ValueBuf::new("a").unwrap();
+        let b = ValueBuf::new("b").unwrap();
+        assert!(a < b);
+        assert_eq!(core::borrow::Borrow::<str>::borrow(&a), "a");
+    }
+
+    #[test]
+    fn value_buf_reject_invalid() {
+        let err = INVALID_CHAR.parse::<ValueBuf>().err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::InvalidChar(';'))
+        );
+
+        let err = INVALID_TOO_LONG.parse::<ValueBuf>().err().unwrap();
+        assert_eq!(
+            err,
+            Error::ParamValueInvalid(InvalidValue::MaxLengthExceeded {
+                max: 64,
+                actual: 65
+            })
+        );
     }
 }
@@ -0,0 +1,41 @@
+//! `arbitrary` value generator tests.
+
+#![cfg(feature = "arbitrary")]
+
+mod common;
+
+use arbitrary::{Arbitrary, Unstructured};
+use common::Lcg;
+use password_hash::{decimal::ArbitraryDecimal, Value, ValueBuf};
+
+/// Deterministic pseudorandom bytes to drive the generators.
+fn entropy(len: usize) -> Vec<u8> {
+    let mut lcg = Lcg::default();
+    (0..len).map(|_| lcg.next_byte()).collect()
+}
+
+#[test]
+fn value_buf_is_always_valid() {
+    let data = entropy(1 << 16);
+    let mut u = Unstructured::new(&data);
+
+    while !u.is_empty() {
+        let value = ValueBuf::arbitrary(&mut u).unwrap();
+        assert!(value.len() <= Value::MAX_LENGTH);
+        assert_eq!(Value::new(value.as_str()).unwrap(), value.as_value());
+    }
+}
+
+#[test]
+fn decimal_is_always_canonical() {
+    let data = entropy(1 << 16);
+    let mut u = Unstructured::new(&data);
+
+    while !u.is_empty() {
+        let decimal = ArbitraryDecimal::arbitrary(&mut u).unwrap();
+        let value = decimal.as_value();
+        let n = value.decimal_signed().unwrap();
+        assert_eq!(n.to_string(), value.as_str());
+        assert!(Value::new_decimal(value.as_str()).is_ok());
+    }
+}
@@ -0,0 +1,27 @@
+//! `arrayvec` integration tests.
+
+#![cfg(feature = "arrayvec")]
+
+use core::cmp::Ordering;
+use password_hash::{Error, Value};
+
+#[test]
+fn to_arrayvec() {
+    let value = Value::new("a+b.c-d").unwrap();
+    let bytes = value.to_arrayvec().unwrap();
+    assert_eq!(bytes.as_slice(), b"a+b.c-d");
+    assert_eq!(bytes.capacity(), Value::MAX_LENGTH);
+
+    let max = "x".repeat(Value::MAX_LENGTH);
+    assert_eq!(Value::new(&max).unwrap().to_arrayvec().unwrap().len(), 64);
+
+    let long = "x".repeat(Value::MAX_LENGTH + 1);
+    let value = Value::new_with_max(&long, long.len()).unwrap();
+    assert_eq!(
+        value.to_arrayvec(),
+        Err(Error::OutputSize {
+            provided: Ordering::Less,
+            expected: Value::MAX_LENGTH + 1
+        })
+    );
+}
@@ -0,0 +1,22 @@
+//! `core::error::Error` tests.
+
+#![cfg(feature = "core-error")]
+
+use core::error::Error as _;
+use password_hash::{errors::InvalidValue, Error, Value};
+
+#[test]
+fn error_source() {
+    let err = Value::new("a;b").err().unwrap();
+    assert_eq!(
+        err,
+        Error::ParamValueInvalid(InvalidValue::InvalidChar(';'))
+    );
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        InvalidValue::InvalidChar(';').to_string()
+    );
+
+    assert!(Error::Algorithm.source().is_none());
+    assert!(InvalidValue::TooShort.source().is_none());
+}
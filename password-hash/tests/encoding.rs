@@ -8,6 +8,8 @@
 //!
 //! <https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#b64>
 
+mod common;
+
 use password_hash::{Output, Salt};
 
 // Example salt encoded as a B64 string.
@@ -36,3 +38,56 @@ fn output_roundtrip() {
     assert_eq!(out.as_ref(), EXAMPLE_OUTPUT_RAW);
     assert_eq!(out.to_string(), EXAMPLE_OUTPUT_B64);
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn encode_vec_roundtrip() {
+    use common::Lcg;
+    use password_hash::Encoding;
+
+    let mut lcg = Lcg::default();
+
+    for &encoding in &[
+        Encoding::B64,
+        Encoding::Bcrypt,
+        Encoding::Crypt,
+        Encoding::Hex,
+    ] {
+        for len in 0..=64 {
+            let bytes: Vec<u8> = (0..len).map(|_| lcg.next_byte()).collect();
+            let encoded = encoding.encode_vec(&bytes);
+            assert!(!encoded.contains('='));
+            assert_eq!(encoded.len(), encoding.encoded_len(&bytes));
+
+            let mut buf = [0u8; 64];
+            assert_eq!(encoding.decode(&encoded, &mut buf).unwrap(), bytes);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn decode_all() {
+    use password_hash::{Encoding, Error, Value};
+
+    let values = [
+        Value::new("AQID").unwrap(),
+        Value::new("").unwrap(),
+        Value::new("BAU").unwrap(),
+    ];
+
+    let mut buf = [0u8; 8];
+    let ranges = Encoding::B64.decode_all(&values, &mut buf).unwrap();
+    assert_eq!(ranges, [0..3, 3..3, 3..5]);
+    assert_eq!(&buf[..5], [1, 2, 3, 4, 5]);
+
+    let (index, err) = Encoding::B64
+        .decode_all(&values, &mut [0u8; 4])
+        .unwrap_err();
+    assert_eq!(index, 2);
+    assert!(matches!(err, Error::B64Encoding(_)));
+
+    let values = [Value::new("AQID").unwrap(), Value::new("a.b").unwrap()];
+    let (index, _) = Encoding::B64.decode_all(&values, &mut buf).unwrap_err();
+    assert_eq!(index, 1);
+}
@@ -0,0 +1,99 @@
+//! Round-trip conformance tests using PHC strings from the Argon2 reference
+//! implementation's test suite.
+//!
+//! Adapted from: <https://github.com/P-H-C/phc-winner-argon2/blob/master/src/test.c>
+//!
+//! Every B64 field (salts, hashes, and the `keyid` and `data` parameters) is
+//! decoded and re-encoded, and every decimal parameter is parsed and
+//! re-formatted, checking the result is identical to the original. None of
+//! these vectors contain a value longer than [`Value::MAX_LENGTH`], so none
+//! of them need to be skipped.
+
+use password_hash::{DataParam, PasswordHash, Value, ValueBuf};
+
+/// Reference PHC strings, along with the decoded salt.
+const VECTORS: &[(&str, &[u8])] = &[
+    (
+        "$argon2i$v=19$m=65536,t=1,p=1$c29tZXNhbHQAAAAAAAAAAA$+r0d29hqEB0yasKr55ZgICsQGSkl0v0kgwhd+U3wyRo",
+        b"somesalt\0\0\0\0\0\0\0\0",
+    ),
+    (
+        "$argon2id$v=19$m=262144,t=2,p=1$c29tZXNhbHQ$eP4eyR+zqlZX1y5xCFTkw9m5GYx0L5YWwvCFvtlbLow",
+        b"somesalt",
+    ),
+    (
+        "$argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+        b"somesalt",
+    ),
+    (
+        "$argon2d$v=19$m=65536,t=2,p=1$YzI5dFpYTmhiSFFBQUFBQUFBQUFBQQ$Jxy74cswY2mq9y+u+iJcJy8EqOp4t/C7DWDzGwGB3IM",
+        b"c29tZXNhbHQAAAAAAAAAAA",
+    ),
+    (
+        "$argon2d$v=19$m=65536,t=2,p=1,keyid=8PDw8A$YzI5dFpYTmhiSFFBQUFBQUFBQUFBQQ$Jxy74cswY2mq9y+u+iJcJy8EqOp4t/C7DWDzGwGB3IM",
+        b"c29tZXNhbHQAAAAAAAAAAA",
+    ),
+    (
+        "$argon2d$v=16$m=32,t=2,p=3,data=Dw8PDw8P$AAAAAAAAAAA$KnH4gniiaFnDvlA1xev3yovC4cnrrI6tnHOYtmja90o",
+        &[0; 8],
+    ),
+    (
+        "$argon2d$v=16$m=32,t=2,p=3,keyid=8PDw8A,data=Dw8PDw8P$AAAAAAAAAAA$KnH4gniiaFnDvlA1xev3yovC4cnrrI6tnHOYtmja90o",
+        &[0; 8],
+    ),
+];
+
+/// Decode the given B64 value and re-encode it, checking the result matches.
+fn assert_b64_roundtrip(value: Value<'_>) -> Vec<u8> {
+    let mut decoded = [0u8; 64];
+    let decoded = value.b64_decode(&mut decoded).unwrap().to_vec();
+
+    let mut encoded = [0u8; Value::MAX_LENGTH];
+    assert_eq!(Value::b64_encode(&decoded, &mut encoded).unwrap(), value);
+    decoded
+}
+
+#[test]
+fn reference_strings_roundtrip() {
+    for &(phc, salt) in VECTORS {
+        let hash = PasswordHash::new(phc).unwrap();
+
+        for (name, value) in hash.params.iter() {
+            match name.as_str() {
+                "m" | "t" | "p" => {
+                    let n = value.decimal().unwrap();
+                    assert_eq!(ValueBuf::from(n).as_value(), value);
+                }
+                "keyid" => assert_eq!(assert_b64_roundtrip(value), [0xf0; 4]),
+                "data" => {
+                    assert_eq!(assert_b64_roundtrip(value), [0x0f; 6]);
+
+                    let mut buf = [0u8; DataParam::MAX_DECODED_LEN];
+                    let data = DataParam::new(value).unwrap();
+                    assert_eq!(data.decode(&mut buf).unwrap(), [0x0f; 6]);
+                }
+                other => panic!("unexpected param: {}", other),
+            }
+        }
+
+        let salt_value = Value::from(hash.salt.unwrap());
+        assert_eq!(assert_b64_roundtrip(salt_value), salt);
+
+        let output = hash.hash.unwrap();
+        assert_eq!(output.len(), 32);
+        let mut encoded = [0u8; 64];
+        let encoded = Value::b64_encode(output.as_bytes(), &mut encoded).unwrap();
+        assert_eq!(encoded, output.to_string().as_str());
+
+        assert_eq!(hash.to_string(), phc);
+    }
+}
+
+#[test]
+fn reference_decimals() {
+    let hash = PasswordHash::new(VECTORS[1].0).unwrap();
+    assert_eq!(hash.version, Some(19));
+    assert_eq!(hash.params.get_decimal("m"), Some(262_144));
+    assert_eq!(hash.params.get_decimal("t"), Some(2));
+    assert_eq!(hash.params.get_decimal("p"), Some(1));
+}
@@ -0,0 +1,27 @@
+//! Serde support tests.
+
+#![cfg(feature = "serde")]
+
+use password_hash::Value;
+
+#[test]
+fn value_roundtrip() {
+    let value = Value::new("a+b.c-d").unwrap();
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "\"a+b.c-d\"");
+
+    let decoded: Value<'_> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn reject_invalid_char() {
+    let err = serde_json::from_str::<Value<'_>>("\"x;y\"").err().unwrap();
+    assert!(err.to_string().contains("invalid character"));
+}
+
+#[test]
+fn reject_too_long() {
+    let json = format!("\"{}\"", "a".repeat(Value::MAX_LENGTH + 1));
+    assert!(serde_json::from_str::<Value<'_>>(&json).is_err());
+}
@@ -0,0 +1,78 @@
+//! Tests for `Value` and `ValueBuf` which rely on `std`, e.g. formatting
+//! values or using owned `ValueBuf` values independently of their source.
+
+use password_hash::{ParamsString, Value, ValueBuf};
+use std::collections::HashMap;
+
+#[test]
+fn value_buf_map_key() {
+    let mut cache = HashMap::new();
+
+    {
+        let params: ParamsString = "m=65536,t=3,p=4".parse().unwrap();
+
+        for (name, value) in params.iter() {
+            cache.insert(ValueBuf::try_from(value).unwrap(), name.to_string());
+        }
+    }
+
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get("65536").map(String::as_str), Some("m"));
+    assert_eq!(cache.get("3").map(String::as_str), Some("t"));
+    assert_eq!(cache.get("5"), None);
+}
+
+#[test]
+fn value_buf_display_roundtrip() {
+    let value_buf = ValueBuf::new("a+b.c-d").unwrap();
+    let s = value_buf.to_string();
+    assert_eq!(s, "a+b.c-d");
+    assert_eq!(s.parse::<ValueBuf>().unwrap(), value_buf);
+}
+
+#[test]
+fn display_honors_formatter_flags() {
+    let value = Value::new("4096").unwrap();
+    assert_eq!(format!("{}", value), "4096");
+    assert_eq!(format!("{:>6}", value), "  4096");
+    assert_eq!(format!("{:*<6}", value), "4096**");
+    assert_eq!(format!("{:^8}", value), "  4096  ");
+    assert_eq!(format!("{:.2}", value), "40");
+
+    let value_buf = ValueBuf::try_from(value).unwrap();
+    assert_eq!(format!("{:>6}", value_buf), "  4096");
+}
+
+#[test]
+fn b64_decode_uninit() {
+    use std::mem::MaybeUninit;
+
+    let value = Value::new("AQIDBAUGBw").unwrap();
+    let mut buf = [MaybeUninit::<u8>::uninit(); 16];
+    let n = value.b64_decode_uninit(&mut buf).unwrap();
+    assert_eq!(n, 7);
+
+    // SAFETY: `b64_decode_uninit` initializes the first `n` elements
+    let decoded: Vec<u8> = buf[..n]
+        .iter()
+        .map(|b| unsafe { b.assume_init() })
+        .collect();
+    assert_eq!(decoded, [1, 2, 3, 4, 5, 6, 7]);
+
+    let mut small = [MaybeUninit::<u8>::uninit(); 4];
+    assert!(value.b64_decode_uninit(&mut small).is_err());
+    assert!(Value::new("AQ.D")
+        .unwrap()
+        .b64_decode_uninit(&mut buf)
+        .is_err());
+}
+
+#[test]
+fn value_macro() {
+    const TABLE: &[Value<'static>] = &[password_hash::value!("19"), password_hash::value!("a.b")];
+    assert_eq!(TABLE[0].decimal().unwrap(), 19);
+    assert_eq!(TABLE[1], "a.b");
+
+    let value = password_hash::value!("x-y");
+    assert_eq!(value, "x-y");
+}
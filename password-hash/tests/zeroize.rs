@@ -0,0 +1,34 @@
+//! `SecretValue` tests.
+
+#![cfg(feature = "zeroize")]
+
+use password_hash::{SecretValue, Value};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+#[test]
+fn secret_value_roundtrip() {
+    let secret = SecretValue::new("c2VjcmV0").unwrap();
+    assert_eq!(secret.as_value(), "c2VjcmV0");
+
+    let mut buf = [0u8; 16];
+    assert_eq!(secret.as_value().b64_decode(&mut buf).unwrap(), b"secret");
+
+    let other = SecretValue::try_from(Value::new("c2VjcmV0").unwrap()).unwrap();
+    assert!(bool::from(secret.ct_eq(&other)));
+    assert!(SecretValue::new("x;y").is_err());
+}
+
+#[test]
+fn secret_value_debug_is_redacted() {
+    let secret = SecretValue::new("hunter2").unwrap();
+    let debug = format!("{:?}", secret);
+    assert!(!debug.contains("hunter2"));
+}
+
+#[test]
+fn secret_value_zeroize() {
+    let mut secret = SecretValue::new("hunter2").unwrap();
+    secret.zeroize();
+    assert_eq!(secret.as_value(), "");
+}